@@ -0,0 +1,261 @@
+use core::{mem, ptr};
+use core::ops::{Deref, DerefMut};
+
+use crate::Scope;
+
+///RAII Scope that can be disarmed and re-armed, running closure in destructor only when armed.
+///
+///Guard is armed on creation, or can be created out of [Scope](struct.Scope.html) via
+///[into_armed](struct.Scope.html#method.into_armed).
+///When disarmed guard is dropped, value is dropped, but closure is not executed.
+///
+///## Example
+///
+///```rust
+///use scope_guard::ArmedScope;
+///
+///let mut is_run = false;
+///{
+///    let mut guard = ArmedScope::new((), |_| is_run = true);
+///    guard.disarm();
+///    assert!(!guard.is_armed());
+///}
+///assert!(!is_run);
+///```
+pub struct ArmedScope<T, F: FnOnce(T)> {
+    scope: mem::ManuallyDrop<Scope<T, F>>,
+    is_armed: bool,
+}
+
+///Token to disarm [ArmedScope](struct.ArmedScope.html), while its value is borrowed.
+///
+///Created via [split_borrow](struct.ArmedScope.html#method.split_borrow).
+pub struct DefuseToken<'a> {
+    is_armed: &'a mut bool,
+}
+
+///Borrowing view of [ArmedScope](struct.ArmedScope.html), that can be passed to subfunction.
+///
+///Gives access to value and allows to disarm or re-arm original guard.
+///Created via [reborrow](struct.ArmedScope.html#method.reborrow).
+pub struct ScopeRef<'a, T> {
+    val: &'a mut T,
+    is_armed: &'a mut bool,
+}
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline(always)]
+    ///Converts into armed guard, which allows to disarm and re-arm it.
+    pub fn into_armed(self) -> ArmedScope<T, F> {
+        ArmedScope {
+            scope: mem::ManuallyDrop::new(self),
+            is_armed: true,
+        }
+    }
+}
+
+impl DefuseToken<'_> {
+    #[inline(always)]
+    ///Disarms guard, preventing closure from running.
+    pub fn defuse(self) {
+        *self.is_armed = false;
+    }
+}
+
+impl<T, F: FnOnce(T)> ArmedScope<T, F> {
+    #[inline(always)]
+    ///Creates new armed instance
+    pub fn new(val: T, dtor: F) -> Self {
+        Scope::new(val, dtor).into_armed()
+    }
+
+    #[inline(always)]
+    ///Returns whether closure will run on drop.
+    pub fn is_armed(&self) -> bool {
+        self.is_armed
+    }
+
+    #[inline(always)]
+    ///Arms guard, making closure run on drop.
+    pub fn arm(&mut self) {
+        self.is_armed = true;
+    }
+
+    #[inline(always)]
+    ///Disarms guard, preventing closure from running on drop.
+    pub fn disarm(&mut self) {
+        self.is_armed = false;
+    }
+
+    #[inline(always)]
+    ///Arms guard if `result` is error, otherwise disarms it.
+    ///
+    ///```
+    ///use scope_guard::ArmedScope;
+    ///use core::cell::Cell;
+    ///
+    ///let attempts = Cell::new(0);
+    ///let cleanups = Cell::new(0);
+    ///loop {
+    ///    let guard = ArmedScope::new((), |_| cleanups.set(cleanups.get() + 1));
+    ///    attempts.set(attempts.get() + 1);
+    ///    let result = match attempts.get() {
+    ///        3 => Ok(()),
+    ///        _ => Err("transient"),
+    ///    };
+    ///    let guard = guard.rearm_on_error(&result);
+    ///    if result.is_ok() {
+    ///        drop(guard);
+    ///        break;
+    ///    }
+    ///}
+    ///assert_eq!(attempts.get(), 3);
+    ///assert_eq!(cleanups.get(), 2);
+    ///```
+    pub fn rearm_on_error<E>(mut self, result: &Result<(), E>) -> Self {
+        self.is_armed = result.is_err();
+        self
+    }
+
+    #[inline(always)]
+    ///Splits guard into mutable reference to value and token to disarm guard.
+    ///
+    ///```
+    ///use scope_guard::{Scope, DefuseToken};
+    ///
+    ///fn commit(val: &mut u32, token: DefuseToken<'_>) {
+    ///    *val += 1;
+    ///    if *val > 1 {
+    ///        token.defuse();
+    ///    }
+    ///}
+    ///
+    ///let mut is_run = false;
+    ///{
+    ///    let mut guard = Scope::new(1, |_| is_run = true).into_armed();
+    ///    let (val, token) = guard.split_borrow();
+    ///    commit(val, token);
+    ///    assert!(!guard.is_armed());
+    ///}
+    ///assert!(!is_run);
+    ///```
+    pub fn split_borrow(&mut self) -> (&mut T, DefuseToken<'_>) {
+        let Self { scope, is_armed } = self;
+        (&mut *scope, DefuseToken { is_armed })
+    }
+
+    #[inline(always)]
+    ///Creates borrowing view of the guard, that can disarm it.
+    ///
+    ///```
+    ///use scope_guard::{ArmedScope, ScopeRef};
+    ///
+    ///fn validate(mut val: ScopeRef<'_, u32>) {
+    ///    *val += 1;
+    ///    if *val > 1 {
+    ///        val.disarm();
+    ///    }
+    ///}
+    ///
+    ///let mut is_run = false;
+    ///{
+    ///    let mut guard = ArmedScope::new(1, |_| is_run = true);
+    ///    validate(guard.reborrow());
+    ///    assert_eq!(*guard, 2);
+    ///    assert!(!guard.is_armed());
+    ///}
+    ///assert!(!is_run);
+    ///```
+    pub fn reborrow(&mut self) -> ScopeRef<'_, T> {
+        let Self { scope, is_armed } = self;
+        ScopeRef {
+            val: &mut *scope,
+            is_armed,
+        }
+    }
+
+    #[inline(always)]
+    fn into_scope(self) -> Scope<T, F> {
+        let scope = unsafe {
+            ptr::read(&*self.scope)
+        };
+        mem::forget(self);
+        scope
+    }
+
+    #[inline]
+    ///Returns underlying data, without executing destructor;
+    pub fn into_inner(self) -> T {
+        self.into_scope().into_inner()
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure from running
+    pub fn forget(self) {
+        self.into_scope().forget()
+    }
+}
+
+impl<T> ScopeRef<'_, T> {
+    #[inline(always)]
+    ///Returns whether closure of original guard will run on drop.
+    pub fn is_armed(&self) -> bool {
+        *self.is_armed
+    }
+
+    #[inline(always)]
+    ///Arms original guard, making closure run on drop.
+    pub fn arm(&mut self) {
+        *self.is_armed = true;
+    }
+
+    #[inline(always)]
+    ///Disarms original guard, preventing closure from running on drop.
+    pub fn disarm(&mut self) {
+        *self.is_armed = false;
+    }
+}
+
+impl<T> Deref for ScopeRef<'_, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.val
+    }
+}
+
+impl<T> DerefMut for ScopeRef<'_, T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.val
+    }
+}
+
+impl<T, F: FnOnce(T)> Deref for ArmedScope<T, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.scope
+    }
+}
+
+impl<T, F: FnOnce(T)> DerefMut for ArmedScope<T, F> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.scope
+    }
+}
+
+impl<T, F: FnOnce(T)> Drop for ArmedScope<T, F> {
+    #[inline]
+    fn drop(&mut self) {
+        let scope = unsafe {
+            ptr::read(&*self.scope)
+        };
+        if !self.is_armed {
+            drop(scope.into_inner());
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use core::{mem, ptr};
+use core::ops::{Deref, DerefMut};
+
+use crate::Scope;
+
+#[inline(always)]
+fn noop<T>(_: &mut T) {
+}
+
+///RAII Scope with fixed capacity of additional steps, running closure in destructor.
+///
+///Up to `N` functions can be registered via [push](#method.push) and on drop they are executed in
+///reverse order of registration, before owning closure.
+///
+///Unlike [stack](struct.Scope.html#method.stack), it doesn't change type of the guard and requires
+///no allocation.
+///
+///## Example
+///
+///```rust
+///use scope_guard::ArrayScope;
+///
+///let mut result = Vec::new();
+///{
+///    let mut guard = ArrayScope::<_, _, 3>::new(Vec::new(), |log| {
+///        result = log;
+///    });
+///    guard.push(|log| log.push("first")).unwrap();
+///    guard.push(|log| log.push("second")).unwrap();
+///    assert_eq!(guard.len(), 2);
+///}
+///assert_eq!(result, ["second", "first"]);
+///```
+pub struct ArrayScope<T, F: FnOnce(T), const N: usize> {
+    scope: Scope<T, F>,
+    steps: [fn(&mut T); N],
+    len: usize,
+}
+
+impl<T, F: FnOnce(T), const N: usize> ArrayScope<T, F, N> {
+    #[inline(always)]
+    ///Creates new instance
+    pub fn new(val: T, dtor: F) -> Self {
+        Self {
+            scope: Scope::new(val, dtor),
+            steps: [noop::<T>; N],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    ///Registers new function to be invoked in scope of the guard.
+    ///
+    ///Returns `step` back as error, if capacity is exhausted.
+    pub fn push(&mut self, step: fn(&mut T)) -> Result<(), fn(&mut T)> {
+        match self.steps.get_mut(self.len) {
+            Some(slot) => {
+                *slot = step;
+                self.len += 1;
+                Ok(())
+            },
+            None => Err(step),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns number of registered functions.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline(always)]
+    ///Returns whether no function is registered.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline(always)]
+    fn into_scope(self) -> Scope<T, F> {
+        let scope = unsafe {
+            ptr::read(&self.scope)
+        };
+        mem::forget(self);
+        scope
+    }
+
+    #[inline]
+    ///Returns underlying data, without executing any destructor;
+    pub fn into_inner(self) -> T {
+        self.into_scope().into_inner()
+    }
+
+    #[inline]
+    ///Forgets self, preventing any closure from running
+    pub fn forget(self) {
+        self.into_scope().forget()
+    }
+}
+
+impl<T, F: FnOnce(T), const N: usize> Deref for ArrayScope<T, F, N> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.scope
+    }
+}
+
+impl<T, F: FnOnce(T), const N: usize> DerefMut for ArrayScope<T, F, N> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.scope
+    }
+}
+
+impl<T, F: FnOnce(T), const N: usize> Drop for ArrayScope<T, F, N> {
+    fn drop(&mut self) {
+        for step in self.steps[..self.len].iter().rev() {
+            step(&mut self.scope);
+        }
+    }
+}
+
+///Creates guard over array, running `dtor` against each element in order on drop.
+///
+///## Example
+///
+///```rust
+///use scope_guard::guard_array;
+///use core::cell::RefCell;
+///
+///let log = RefCell::new(Vec::new());
+///{
+///    let mut guard = guard_array([1, 2, 3], |slot| log.borrow_mut().push(*slot));
+///    for slot in guard.iter_mut() {
+///        *slot *= 10;
+///    }
+///}
+///assert_eq!(*log.borrow(), [10, 20, 30]);
+///```
+pub fn guard_array<T, F: Fn(&mut T), const N: usize>(arr: [T; N], dtor: F) -> Scope<[T; N], impl FnOnce([T; N])> {
+    Scope::new(arr, move |mut arr| {
+        for item in arr.iter_mut() {
+            dtor(item);
+        }
+    })
+}
@@ -0,0 +1,130 @@
+use core::cell::Cell;
+use core::ops::{Deref, DerefMut};
+
+use alloc::rc::Rc;
+
+use crate::Scope;
+use crate::strategy::is_panicking;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Pending,
+    Ran,
+    Forgotten,
+}
+
+///Test helper wrapping guard to assert that its closure actually runs.
+///
+///Created together with [RunCheck](struct.RunCheck.html) via [new](#method.new).
+///Explicit `forget` or `into_inner` are treated as intended, while leaking guard by any other means
+///(e.g. `core::mem::forget` or reference cycle) causes `RunCheck` to panic on drop.
+///
+///Available with `testutil` feature, which is intended to be enabled only in `dev-dependencies`.
+///
+///## Example
+///
+///```rust
+///use scope_guard::{Scope, AssertRuns};
+///
+///let (guard, check) = AssertRuns::new(Scope::new(1, |_| ()));
+///assert_eq!(*guard, 1);
+///drop(guard);
+///assert!(check.has_run());
+///
+///let (guard, _check) = AssertRuns::new(Scope::new(1, |_| ()));
+///guard.forget();
+///```
+///
+///Leaked guard is detected:
+///
+///```rust,should_panic
+///use scope_guard::{Scope, AssertRuns};
+///
+///let (guard, _check) = AssertRuns::new(Scope::new(1, |_| ()));
+///core::mem::forget(guard);
+///```
+pub struct AssertRuns<T, F: FnOnce(T)> {
+    scope: Option<Scope<T, F>>,
+    state: Rc<Cell<State>>,
+}
+
+#[must_use = "RunCheck asserts only when dropped at the end of test"]
+///Checker, that panics on drop if closure of its [AssertRuns](struct.AssertRuns.html) never ran.
+///
+///Panic is suppressed if thread is already panicking.
+pub struct RunCheck {
+    state: Rc<Cell<State>>,
+}
+
+impl<T, F: FnOnce(T)> AssertRuns<T, F> {
+    #[inline]
+    ///Wraps `scope`, returning it together with checker.
+    pub fn new(scope: Scope<T, F>) -> (Self, RunCheck) {
+        let state = Rc::new(Cell::new(State::Pending));
+        let check = RunCheck {
+            state: state.clone(),
+        };
+        (Self { scope: Some(scope), state }, check)
+    }
+
+    #[inline]
+    fn defuse(mut self) -> Scope<T, F> {
+        self.state.set(State::Forgotten);
+        self.scope.take().unwrap()
+    }
+
+    #[inline]
+    ///Returns underlying data, without executing destructor;
+    pub fn into_inner(self) -> T {
+        self.defuse().into_inner()
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure from running
+    pub fn forget(self) {
+        self.defuse().forget()
+    }
+}
+
+impl RunCheck {
+    #[inline]
+    ///Returns whether closure of the guard ran.
+    pub fn has_run(&self) -> bool {
+        self.state.get() == State::Ran
+    }
+}
+
+impl<T, F: FnOnce(T)> Deref for AssertRuns<T, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.scope.as_ref().unwrap()
+    }
+}
+
+impl<T, F: FnOnce(T)> DerefMut for AssertRuns<T, F> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.scope.as_mut().unwrap()
+    }
+}
+
+impl<T, F: FnOnce(T)> Drop for AssertRuns<T, F> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(scope) = self.scope.take() {
+            self.state.set(State::Ran);
+            drop(scope);
+        }
+    }
+}
+
+impl Drop for RunCheck {
+    #[inline]
+    fn drop(&mut self) {
+        if self.state.get() == State::Pending && is_panicking() != Some(true) {
+            panic!("Scope closure never ran, but guard was not explicitly forgotten");
+        }
+    }
+}
@@ -1,13 +1,39 @@
 extern crate std;
 
 use std::boxed::Box;
+use std::vec::Vec;
+use std::vec;
 use std::panic;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use core::future::Future;
 use core::any::Any;
 use core::pin::Pin;
 use core::task;
 
+use crate::Scope;
+
+#[doc(hidden)]
+//Polls future to completion on current thread, for use in doctests.
+pub fn __block_on<F: Future>(fut: F) -> F::Output {
+    struct Noop;
+
+    impl std::task::Wake for Noop {
+        fn wake(self: Arc<Self>) {
+        }
+    }
+
+    let waker = task::Waker::from(Arc::new(Noop));
+    let mut ctx = task::Context::from_waker(&waker);
+    let mut fut = Box::pin(fut);
+    loop {
+        if let task::Poll::Ready(result) = fut.as_mut().poll(&mut ctx) {
+            break result;
+        }
+    }
+}
+
 #[must_use]
 ///Wraps to propagate panic as error.
 ///
@@ -28,6 +54,33 @@ use core::task;
 ///    }
 ///}
 ///```
+///
+///## Panic strategy
+///
+///When compiled with `panic = "abort"`, panic cannot be caught, so future is polled directly and
+///its output is always wrapped into `Ok`.
+///This applies to all async scopes in this crate too.
+///
+///```rust
+///use scope_guard::CatchUnwindFut;
+///# use scope_guard::__block_on as block_on;
+///
+///let result = block_on(CatchUnwindFut(async { 1 }));
+///assert_eq!(result.ok(), Some(1));
+///
+/////Pending future is polled again, regardless of panic strategy
+///let mut polls = 0;
+///let result = block_on(CatchUnwindFut(core::future::poll_fn(move |ctx| {
+///    polls += 1;
+///    if polls < 3 {
+///        ctx.waker().wake_by_ref();
+///        core::task::Poll::Pending
+///    } else {
+///        core::task::Poll::Ready(polls)
+///    }
+///})));
+///assert_eq!(result.ok(), Some(3));
+///```
 pub struct CatchUnwindFut<F: panic::UnwindSafe>(pub F);
 
 impl<F: Future + panic::UnwindSafe> Future for CatchUnwindFut<F> {
@@ -39,6 +92,10 @@ impl<F: Future + panic::UnwindSafe> Future for CatchUnwindFut<F> {
             self.map_unchecked_mut(|this| &mut this.0)
         };
 
+        #[cfg(panic = "abort")]
+        return fut.poll(ctx).map(Ok);
+
+        #[cfg(not(panic = "abort"))]
         match panic::catch_unwind(panic::AssertUnwindSafe(|| fut.poll(ctx))) {
             Ok(task::Poll::Pending) => task::Poll::Pending,
             Ok(task::Poll::Ready(res)) => task::Poll::Ready(Ok(res)),
@@ -47,6 +104,107 @@ impl<F: Future + panic::UnwindSafe> Future for CatchUnwindFut<F> {
     }
 }
 
+#[cfg(feature = "nightly")]
+#[must_use]
+///Wraps async iterator (i.e. stream) to propagate panic of each `poll_next` as error item.
+///
+///Available only on nightly with `nightly` feature.
+///
+///Stream is terminated after panic: error item is the last one, after which `None` is returned
+///without polling underlying stream again.
+///Same as with [CatchUnwindFut](struct.CatchUnwindFut.html), panic cannot be caught when compiled
+///with `panic = "abort"`.
+///
+///## Example
+///
+///```rust
+///#![feature(async_iterator)]
+///use scope_guard::CatchUnwindStream;
+///use core::async_iter::AsyncIterator;
+///use core::future::poll_fn;
+///use core::pin::Pin;
+///use core::task::{Context, Poll};
+///# use scope_guard::__block_on as block_on;
+///
+///struct Numbers(u32);
+///
+///impl AsyncIterator for Numbers {
+///    type Item = u32;
+///
+///    fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u32>> {
+///        self.0 += 1;
+///        match self.0 {
+///            2 => panic!("second item"),
+///            item => Poll::Ready(Some(item)),
+///        }
+///    }
+///}
+///
+///let mut stream = CatchUnwindStream::new(Numbers(0));
+///let mut next = || block_on(poll_fn(|ctx| Pin::new(&mut stream).poll_next(ctx)));
+///assert_eq!(next().unwrap().ok(), Some(1));
+///let error = next().unwrap().unwrap_err();
+///assert_eq!(*error.downcast_ref::<&str>().unwrap(), "second item");
+///assert!(next().is_none());
+///```
+pub struct CatchUnwindStream<S> {
+    stream: S,
+    is_done: bool,
+}
+
+#[cfg(feature = "nightly")]
+impl<S> CatchUnwindStream<S> {
+    #[inline(always)]
+    ///Creates new instance
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            is_done: false,
+        }
+    }
+
+    #[inline(always)]
+    ///Returns underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<S: core::async_iter::AsyncIterator + panic::UnwindSafe> core::async_iter::AsyncIterator for CatchUnwindStream<S> {
+    type Item = Result<S::Item, Box<dyn Any + Send + 'static>>;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut task::Context<'_>) -> task::Poll<Option<Self::Item>> {
+        let this = unsafe {
+            self.get_unchecked_mut()
+        };
+        if this.is_done {
+            return task::Poll::Ready(None);
+        }
+
+        let stream = unsafe {
+            Pin::new_unchecked(&mut this.stream)
+        };
+
+        #[cfg(panic = "abort")]
+        return stream.poll_next(ctx).map(|item| item.map(Ok));
+
+        #[cfg(not(panic = "abort"))]
+        match panic::catch_unwind(panic::AssertUnwindSafe(|| stream.poll_next(ctx))) {
+            Ok(task::Poll::Pending) => task::Poll::Pending,
+            Ok(task::Poll::Ready(Some(item))) => task::Poll::Ready(Some(Ok(item))),
+            Ok(task::Poll::Ready(None)) => {
+                this.is_done = true;
+                task::Poll::Ready(None)
+            },
+            Err(error) => {
+                this.is_done = true;
+                task::Poll::Ready(Some(Err(error)))
+            },
+        }
+    }
+}
+
 ///Executes future, making sure to perform cleanup regardless of whether `fut` is successful or
 ///panics.
 ///
@@ -92,6 +250,476 @@ pub async fn async_scope<
     dtor.await;
     match result {
         Ok(result) => result,
-        Err(error) => std::panic::resume_unwind(error),
+        Err(error) => crate::strategy::resume_unwind(error),
+    }
+}
+
+///Executes future, that borrows local state for lifetime `'a`, with the same guarantees as
+///[async_scope](fn.async_scope.html).
+///
+///Borrowed state (e.g. `&mut` connection or `&RefCell`) is rarely `UnwindSafe`, hence `fut` is
+///assumed to be unwind safe, as after its panic only `dtor` can observe borrowed state, before
+///panic is resumed.
+///
+///## Example
+///
+///```rust
+///use scope_guard::async_scope_borrowed;
+///use core::cell::RefCell;
+///# use scope_guard::__block_on as block_on;
+///
+///let conn = RefCell::new(Vec::new());
+///let result = block_on(async_scope_borrowed(|conn: &RefCell<Vec<_>>| async move {
+///    conn.borrow_mut().push("close");
+///}, &conn, async {
+///    conn.borrow_mut().push("query");
+///    1
+///}));
+///assert_eq!(result, 1);
+///assert_eq!(*conn.borrow(), ["query", "close"]);
+///```
+pub fn async_scope_borrowed<
+    'a,
+    R: 'a,
+    F: Future<Output = R> + 'a,
+    DTORARGS: 'a,
+    DTOR: Future<Output = ()> + 'a,
+    DTORFN: FnOnce(DTORARGS) -> DTOR + 'a,
+>(
+    dtor: DTORFN,
+    args: DTORARGS,
+    fut: F,
+) -> impl Future<Output = R> + 'a {
+    async_scope(dtor, args, panic::AssertUnwindSafe(fut))
+}
+
+///Executes future, making sure to perform cleanup regardless of whether `fut` is successful or
+///panics, collecting all panics instead of resuming them.
+///
+///## Arguments:
+///- `dtor` - Generic callback that accepts `args` as its only incoming parameter;
+///- `args` - Generic arguments that are passed as it is to the `dtor`;
+///- `fut` - Future to execute before calling `dtor`. Regardless of success, `dtor` is always
+///executed.
+///
+///Returns `Output` of `fut` if neither `fut` nor `dtor` panic.
+///Otherwise returns panic payloads with `fut` panic first, followed by `dtor` panic.
+///
+///## Example
+///
+///```rust
+///use scope_guard::async_scope_collect;
+///# use scope_guard::__block_on as block_on;
+///
+///async fn dtor(_args: ()) {
+///    panic!("dtor");
+///}
+///
+///let fut = async {
+///    panic!("fut")
+///};
+///
+///let panics = block_on(async_scope_collect(dtor, (), fut)).unwrap_err();
+///assert_eq!(panics.len(), 2);
+///assert_eq!(*panics[0].downcast_ref::<&str>().unwrap(), "fut");
+///assert_eq!(*panics[1].downcast_ref::<&str>().unwrap(), "dtor");
+///```
+pub async fn async_scope_collect<
+    R,
+    F: Future<Output = R> + panic::UnwindSafe,
+    DTORARGS,
+    DTOR: Future<Output = ()> + panic::UnwindSafe,
+    DTORFN: FnOnce(DTORARGS) -> DTOR,
+>(
+    dtor: DTORFN,
+    args: DTORARGS,
+    fut: F,
+) -> Result<R, Vec<Box<dyn Any + Send + 'static>>> {
+    let result = CatchUnwindFut(fut).await;
+    let dtor = (dtor)(args);
+    let dtor_result = CatchUnwindFut(dtor).await;
+    match (result, dtor_result) {
+        (Ok(result), Ok(())) => Ok(result),
+        (Ok(_), Err(dtor_error)) => Err(vec![dtor_error]),
+        (Err(error), Ok(())) => Err(vec![error]),
+        (Err(error), Err(dtor_error)) => Err(vec![error, dtor_error]),
+    }
+}
+
+///Executes future, then runs synchronous `hook` over `args`, followed by async `dtor`.
+///
+///Ordering is always: `fut` is awaited, `hook` is run, `dtor` is awaited and only then panic of
+///`fut`, if any, is resumed.
+///This is useful when part of teardown is synchronous (e.g. setting flag), while the rest is
+///asynchronous (e.g. closing connection).
+///
+///## Example
+///
+///```rust
+///use scope_guard::async_scope_hook;
+///use core::cell::RefCell;
+///# use scope_guard::__block_on as block_on;
+///
+///let log = RefCell::new(Vec::new());
+///let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+///    block_on(async_scope_hook(|log: &RefCell<Vec<_>>| async move {
+///        log.borrow_mut().push("close");
+///    }, &log, |log| log.borrow_mut().push("hook"), std::panic::AssertUnwindSafe(async {
+///        log.borrow_mut().push("fut");
+///        panic!("fut failed");
+///    })))
+///}));
+///assert!(result.is_err());
+///assert_eq!(*log.borrow(), ["fut", "hook", "close"]);
+///```
+pub async fn async_scope_hook<
+    R,
+    F: Future<Output = R> + panic::UnwindSafe,
+    DTORARGS,
+    HOOK: FnOnce(&mut DTORARGS),
+    DTOR: Future<Output = ()>,
+    DTORFN: FnOnce(DTORARGS) -> DTOR,
+>(
+    dtor: DTORFN,
+    mut args: DTORARGS,
+    hook: HOOK,
+    fut: F,
+) -> R {
+    let result = CatchUnwindFut(fut).await;
+    hook(&mut args);
+    let dtor = (dtor)(args);
+    dtor.await;
+    match result {
+        Ok(result) => result,
+        Err(error) => crate::strategy::resume_unwind(error),
+    }
+}
+
+#[macro_export]
+///Creates [async_scope](fn.async_scope.html), allowing to supply multiple arguments to `dtor`.
+///
+///Last argument is always future to execute, while arguments between `dtor` and future are
+///packed into tuple, similarly to [scope_guard](macro.scope_guard.html).
+///
+///## Example
+///
+///```rust
+///use scope_guard::async_scope_guard;
+///# use scope_guard::__block_on as block_on;
+///
+///let mut val = 0;
+///let mut is_run = false;
+///let result = block_on(async_scope_guard!(|(val, is_run)| async move {
+///    *val = 1;
+///    *is_run = true;
+///}, &mut val, &mut is_run, async {
+///    2
+///}));
+///assert_eq!(result, 2);
+///assert_eq!(val, 1);
+///assert!(is_run);
+///
+///let is_run = core::cell::Cell::new(false);
+///block_on(async_scope_guard!(|| async {
+///    is_run.set(true);
+///}, async {}));
+///assert!(is_run.get());
+///```
+macro_rules! async_scope_guard {
+    (@args $dtor:expr, [$($args:expr),*], $fut:expr) => {
+        $crate::async_scope($dtor, ($($args),*), $fut)
+    };
+    (@args $dtor:expr, [$($args:expr),*], $arg:expr, $($rest:expr),+) => {
+        $crate::async_scope_guard!(@args $dtor, [$($args,)* $arg], $($rest),+)
+    };
+    ($dtor:expr, $fut:expr) => {
+        $crate::async_scope(|_| $dtor(), (), $fut)
+    };
+    ($dtor:expr, $arg:expr, $fut:expr) => {
+        $crate::async_scope($dtor, $arg, $fut)
+    };
+    ($dtor:expr, $($rest:expr),+) => {
+        $crate::async_scope_guard!(@args $dtor, [], $($rest),+)
+    };
+}
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    ///Finishes guard in async context, running its closure first and awaiting `follow` after.
+    ///
+    ///This allows to layer asynchronous teardown on top of synchronous one.
+    ///Guard is owned by returned future, hence if it is dropped before completion, closure runs
+    ///anyway, while `follow` is never started.
+    ///
+    ///## Example
+    ///
+    ///```rust
+    ///use scope_guard::Scope;
+    ///use core::cell::RefCell;
+    ///# use scope_guard::__block_on as block_on;
+    ///
+    ///let log = RefCell::new(Vec::new());
+    ///block_on(async {
+    ///    let guard = Scope::new("conn", |val| log.borrow_mut().push(format!("release {}", val)));
+    ///    log.borrow_mut().push(format!("use {}", *guard));
+    ///    guard.then_async(|| async { log.borrow_mut().push("notify peers".to_owned()) }).await;
+    ///});
+    ///assert_eq!(*log.borrow(), ["use conn", "release conn", "notify peers"]);
+    ///```
+    pub async fn then_async<FOLLOW: Future<Output = ()>, FOLLOWFN: FnOnce() -> FOLLOW>(self, follow: FOLLOWFN) {
+        drop(self);
+        let follow = (follow)();
+        follow.await
+    }
+}
+
+///Executes async `body` with sync `guard` alive, dropping `guard` right after `body` finishes.
+///
+///`guard` is owned by returned future, hence closure of the `guard` is executed even if returned
+///future is cancelled (i.e. dropped before completion).
+///
+///## Example
+///
+///```rust
+///use scope_guard::{scope_async, Scope};
+///use core::future::Future;
+///use core::cell::Cell;
+///use std::task::{Context, Poll, Wake, Waker};
+///
+///struct Noop;
+///impl Wake for Noop {
+///    fn wake(self: std::sync::Arc<Self>) {}
+///}
+///
+///let waker = Waker::from(std::sync::Arc::new(Noop));
+///let mut ctx = Context::from_waker(&waker);
+///
+///let result = Cell::new(0);
+///let guard = Scope::new(0, |val| result.set(val));
+///let mut fut = Box::pin(scope_async(guard, async |guard| {
+///    **guard = 1;
+///    std::future::pending::<()>().await;
+///}));
+///
+///assert!(fut.as_mut().poll(&mut ctx).is_pending());
+///assert_eq!(result.get(), 0);
+///drop(fut);
+///assert_eq!(result.get(), 1);
+///```
+pub async fn scope_async<
+    T,
+    F: FnOnce(T),
+    R,
+    B: AsyncFnOnce(&mut Scope<T, F>) -> R,
+>(
+    mut guard: Scope<T, F>,
+    body: B,
+) -> R {
+    body(&mut guard).await
+}
+
+///Executes async `body` with access to `val`, then moves `val` into async `dtor`, regardless of
+///whether `body` is successful or panics.
+///
+///Unlike [async_scope](fn.async_scope.html), `body` borrows value that is later consumed by
+///`dtor`.
+///`body` is assumed to be unwind safe, as `val` is always passed to `dtor` after panic.
+///
+///Returns `Output` of `body` or resumes panic after `dtor` is finished.
+///
+///## Example
+///
+///```rust
+///use scope_guard::async_scope_value;
+///# use scope_guard::__block_on as block_on;
+///
+///struct Connection {
+///    sent: Vec<&'static str>,
+///}
+///
+///impl Connection {
+///    async fn send(&mut self, msg: &'static str) {
+///        self.sent.push(msg);
+///    }
+///
+///    async fn close(mut self) -> Vec<&'static str> {
+///        self.sent.push("close");
+///        self.sent
+///    }
+///}
+///
+///let mut log = Vec::new();
+///let result = block_on(async_scope_value(Connection { sent: Vec::new() }, async |conn| {
+///    log = conn.close().await;
+///}, async |conn| {
+///    conn.send("hello").await;
+///    1
+///}));
+///assert_eq!(result, 1);
+///assert_eq!(log, ["hello", "close"]);
+///```
+pub async fn async_scope_value<
+    T,
+    R,
+    B: AsyncFnOnce(&mut T) -> R,
+    DTOR: AsyncFnOnce(T),
+>(
+    mut val: T,
+    dtor: DTOR,
+    body: B,
+) -> R {
+    let result = CatchUnwindFut(panic::AssertUnwindSafe(body(&mut val))).await;
+    dtor(val).await;
+    match result {
+        Ok(result) => result,
+        Err(error) => crate::strategy::resume_unwind(error),
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+///Cooperative cancellation token, shared between clones.
+///
+///Token does not interrupt futures by itself: future is expected to check
+///[is_cancelled](#method.is_cancelled) and finish early.
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    #[inline(always)]
+    ///Creates new token, that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline(always)]
+    ///Requests cancellation for every clone of the token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    #[inline(always)]
+    ///Returns whether cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+///Executes future, then performs async cleanup aware of whether `token` was cancelled.
+///
+///`dtor` receives `true` if cancellation was requested by the time `fut` finished, allowing to
+///distinguish graceful shutdown from normal end, and `false` otherwise.
+///Same as with [async_scope](fn.async_scope.html), `dtor` is always executed, even if `fut`
+///panics, after which panic is resumed.
+///
+///## Example
+///
+///```rust
+///use scope_guard::{async_scope_cancellable, CancelToken};
+///# use scope_guard::__block_on as block_on;
+///
+///let log = &std::cell::RefCell::new(Vec::new());
+///let token = CancelToken::new();
+///
+///let processed = block_on(async_scope_cancellable(&token, |is_cancelled| async move {
+///    log.borrow_mut().push(if is_cancelled { "flush partial" } else { "flush all" });
+///}, async {
+///    let mut processed = 0;
+///    for _ in 0..10 {
+///        if token.is_cancelled() {
+///            break;
+///        }
+///        processed += 1;
+///        if processed == 3 {
+///            token.clone().cancel();
+///        }
+///    }
+///    processed
+///}));
+///assert_eq!(processed, 3);
+///
+///let token = CancelToken::new();
+///block_on(async_scope_cancellable(&token, |is_cancelled| async move {
+///    log.borrow_mut().push(if is_cancelled { "flush partial" } else { "flush all" });
+///}, async {}));
+///assert_eq!(*log.borrow(), ["flush partial", "flush all"]);
+///```
+pub async fn async_scope_cancellable<
+    R,
+    F: Future<Output = R> + panic::UnwindSafe,
+    DTOR: Future<Output = ()>,
+    DTORFN: FnOnce(bool) -> DTOR,
+>(
+    token: &CancelToken,
+    dtor: DTORFN,
+    fut: F,
+) -> R {
+    let result = CatchUnwindFut(fut).await;
+    let dtor = (dtor)(token.is_cancelled());
+    dtor.await;
+    match result {
+        Ok(result) => result,
+        Err(error) => crate::strategy::resume_unwind(error),
+    }
+}
+
+///Executes future, then performs fallible async cleanup, retrying it up to `retries` times.
+///
+///Before each retry `backoff` is awaited with number of failed attempts so far, starting from 1.
+///In total `dtor` is attempted at most `retries + 1` times.
+///
+///Returns `Output` of `fut` alongside result of the last cleanup attempt, i.e. `Err` only if all
+///attempts failed.
+///If `fut` panics, cleanup is still performed, after which panic is resumed, discarding cleanup
+///result.
+///
+///## Example
+///
+///```rust
+///use scope_guard::async_scope_retry;
+///use core::cell::{Cell, RefCell};
+///# use scope_guard::__block_on as block_on;
+///
+///let attempts = &Cell::new(0);
+///let backoffs = &RefCell::new(Vec::new());
+///let (result, cleanup) = block_on(async_scope_retry(|| async move {
+///    attempts.set(attempts.get() + 1);
+///    match attempts.get() {
+///        3 => Ok(()),
+///        _ => Err("connection reset"),
+///    }
+///}, 5, |attempt| async move { backoffs.borrow_mut().push(attempt) }, async {
+///    1
+///}));
+///assert_eq!(result, 1);
+///assert_eq!(cleanup, Ok(()));
+///assert_eq!(attempts.get(), 3);
+///assert_eq!(*backoffs.borrow(), [1, 2]);
+///
+///let (_, cleanup) = block_on(async_scope_retry(|| async { Err("unreachable") }, 1, |_| async {}, async {}));
+///assert_eq!(cleanup, Err("unreachable"));
+///```
+pub async fn async_scope_retry<
+    R,
+    E,
+    F: Future<Output = R> + panic::UnwindSafe,
+    DTOR: Future<Output = Result<(), E>>,
+    DTORFN: FnMut() -> DTOR,
+    BACKOFF: Future<Output = ()>,
+    BACKOFFFN: FnMut(usize) -> BACKOFF,
+>(
+    mut dtor: DTORFN,
+    retries: usize,
+    mut backoff: BACKOFFFN,
+    fut: F,
+) -> (R, Result<(), E>) {
+    let result = CatchUnwindFut(fut).await;
+    let mut cleanup = dtor().await;
+    let mut attempt = 0;
+    while cleanup.is_err() && attempt < retries {
+        attempt += 1;
+        backoff(attempt).await;
+        cleanup = dtor().await;
+    }
+    match result {
+        Ok(result) => (result, cleanup),
+        Err(error) => crate::strategy::resume_unwind(error),
     }
 }
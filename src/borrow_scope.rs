@@ -0,0 +1,86 @@
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+
+use crate::Scope;
+
+///RAII Scope, statically tied to lifetime `'a` of external borrow, running closure in destructor.
+///
+///Created via [borrowing](struct.Scope.html#method.borrowing) or
+///[borrowing_from](struct.Scope.html#method.borrowing_from).
+///
+///## Example
+///
+///```rust
+///use scope_guard::Scope;
+///
+///let resource = vec![1];
+///let mut is_run = false;
+///{
+///    let _guard = Scope::borrowing_from(&resource, (), |_| is_run = true);
+///}
+///assert!(is_run);
+///```
+///
+///Guard cannot outlive borrow:
+///
+///```compile_fail
+///use scope_guard::Scope;
+///
+///let guard;
+///{
+///    let resource = vec![1];
+///    guard = Scope::borrowing_from(&resource, (), |_| ());
+///}
+///drop(guard);
+///```
+pub struct BorrowScope<'a, T, F: FnOnce(T)> {
+    scope: Scope<T, F>,
+    _borrow: PhantomData<&'a ()>,
+}
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline(always)]
+    ///Creates new instance, which cannot outlive lifetime `'a`.
+    pub fn borrowing<'a>(val: T, dtor: F) -> BorrowScope<'a, T, F> {
+        BorrowScope {
+            scope: Scope::new(val, dtor),
+            _borrow: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    ///Creates new instance, which cannot outlive `borrow`.
+    pub fn borrowing_from<'a, R: ?Sized>(_borrow: &'a R, val: T, dtor: F) -> BorrowScope<'a, T, F> {
+        Self::borrowing(val, dtor)
+    }
+}
+
+impl<T, F: FnOnce(T)> BorrowScope<'_, T, F> {
+    #[inline]
+    ///Returns underlying data, without executing destructor;
+    pub fn into_inner(self) -> T {
+        self.scope.into_inner()
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure from running
+    pub fn forget(self) {
+        self.scope.forget()
+    }
+}
+
+impl<T, F: FnOnce(T)> Deref for BorrowScope<'_, T, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.scope
+    }
+}
+
+impl<T, F: FnOnce(T)> DerefMut for BorrowScope<'_, T, F> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.scope
+    }
+}
@@ -0,0 +1,203 @@
+use core::mem;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::{Scope, DerefScope};
+
+///RAII Scope with type-erased closure.
+pub type BoxScope<'a, T> = Scope<T, Box<dyn FnOnce(T) + 'a>>;
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline]
+    ///Erases type of closure, moving it into `Box`, without running it.
+    ///
+    ///```
+    ///use scope_guard::{Scope, BoxScope};
+    ///
+    ///let mut result = 0;
+    ///{
+    ///    let guard: BoxScope<u32> = Scope::new(1, |val| {
+    ///        result = val;
+    ///    }).boxed();
+    ///    assert_eq!(*guard, 1);
+    ///}
+    ///assert_eq!(result, 1);
+    ///```
+    pub fn boxed<'a>(self) -> BoxScope<'a, T> where F: 'a {
+        let (value, dtor, backtrace) = self.into_parts();
+        Scope::from_parts(value, Box::new(dtor), backtrace)
+    }
+
+    #[inline]
+    ///Splits guard into value and type-erased closure, without running either.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::RefCell;
+    ///
+    ///let log = RefCell::new(Vec::new());
+    ///let mut values = Vec::new();
+    ///let mut dtors: Vec<Box<dyn FnOnce(u32)>> = Vec::new();
+    ///
+    ///let (value, dtor) = Scope::new(1, |val| log.borrow_mut().push(val)).into_parts_boxed();
+    ///values.push(value);
+    ///dtors.push(dtor);
+    ///let (value, dtor) = Scope::new(2, |val| log.borrow_mut().push(val * 10)).into_parts_boxed();
+    ///values.push(value);
+    ///dtors.push(dtor);
+    ///assert!(log.borrow().is_empty());
+    ///
+    ///for (value, dtor) in values.into_iter().zip(dtors) {
+    ///    dtor(value);
+    ///}
+    ///assert_eq!(*log.borrow(), [1, 20]);
+    ///```
+    pub fn into_parts_boxed<'a>(self) -> (T, Box<dyn FnOnce(T) + 'a>) where F: 'a {
+        let (value, dtor, _) = self.into_parts();
+        (value, Box::new(dtor))
+    }
+}
+
+impl<'a, T> BoxScope<'a, T> {
+    #[inline]
+    ///Extracts closure, replacing it with no-op, so that guard no longer runs it on drop.
+    ///
+    ///```
+    ///use scope_guard::{Scope, BoxScope};
+    ///use core::cell::Cell;
+    ///
+    ///let result = Cell::new(0);
+    ///let mut guard: BoxScope<u32> = Scope::new(1, |val| result.set(val)).boxed();
+    ///let dtor = guard.take_dtor();
+    ///drop(guard);
+    ///assert_eq!(result.get(), 0);
+    ///
+    ///dtor(2);
+    ///assert_eq!(result.get(), 2);
+    ///```
+    pub fn take_dtor(&mut self) -> Box<dyn FnOnce(T) + 'a> {
+        mem::replace(&mut *self.dtor, Box::new(|_| ()))
+    }
+}
+
+///Defuses every guard in `guards`, without running any closure.
+///
+///Unlike [forget](struct.Scope.html#method.forget), values are not leaked, but dropped normally.
+///
+///```
+///use scope_guard::{forget_all, Scope, BoxScope};
+///use core::cell::Cell;
+///
+///struct Value<'a>(&'a Cell<u32>);
+///
+///impl Drop for Value<'_> {
+///    fn drop(&mut self) {
+///        self.0.set(self.0.get() + 1);
+///    }
+///}
+///
+///let drops = Cell::new(0);
+///let runs = Cell::new(0);
+///let guards: Vec<BoxScope<Value>> = (0..3).map(|_| {
+///    Scope::new(Value(&drops), |_| runs.set(runs.get() + 1)).boxed()
+///}).collect();
+///forget_all(guards);
+///assert_eq!(runs.get(), 0);
+///assert_eq!(drops.get(), 3);
+///
+/////Guards are defused before any value is dropped, even if drop of value panics
+///struct Panicky;
+///
+///impl Drop for Panicky {
+///    fn drop(&mut self) {
+///        panic!("drop failed");
+///    }
+///}
+///
+///let guards: Vec<BoxScope<Option<Panicky>>> = vec![
+///    Scope::new(Some(Panicky), |_| runs.set(runs.get() + 1)).boxed(),
+///    Scope::new(None, |_| runs.set(runs.get() + 1)).boxed(),
+///    Scope::new(None, |_| runs.set(runs.get() + 1)).boxed(),
+///];
+///let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| forget_all(guards)));
+///assert!(result.is_err());
+///assert_eq!(runs.get(), 0);
+///```
+pub fn forget_all<T>(guards: Vec<BoxScope<'_, T>>) {
+    let values: Vec<T> = guards.into_iter().map(Scope::into_inner).collect();
+    drop(values);
+}
+
+///Runs closure of every guard in `guards` right away, in reverse order.
+///
+///```
+///use scope_guard::{run_all, Scope, BoxScope};
+///use core::cell::RefCell;
+///
+///let log = RefCell::new(Vec::new());
+///let guards: Vec<BoxScope<u32>> = (0..3).map(|idx| {
+///    Scope::new(idx, |idx| log.borrow_mut().push(idx)).boxed()
+///}).collect();
+///run_all(guards);
+///assert_eq!(*log.borrow(), [2, 1, 0]);
+///
+/////Order is kept, even if closure panics
+///log.borrow_mut().clear();
+///let guards: Vec<BoxScope<u32>> = (0..4).map(|idx| {
+///    Scope::new(idx, |idx| match idx {
+///        2 => panic!("cleanup failed"),
+///        idx => log.borrow_mut().push(idx),
+///    }).boxed()
+///}).collect();
+///let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| run_all(guards)));
+///assert!(result.is_err());
+///assert_eq!(*log.borrow(), [3, 1, 0]);
+///```
+pub fn run_all<T>(mut guards: Vec<BoxScope<'_, T>>) {
+    guards.reverse();
+    drop(guards);
+}
+
+///RAII Scope over boxed value (e.g. trait object), dereferencing directly to it.
+///
+///Closure receives `Box` on drop.
+///
+///```
+///use scope_guard::{DerefScope, DynValueScope};
+///use std::io::Write;
+///
+///let mut written = 0;
+///{
+///    let sink: Box<dyn Write> = Box::new(Vec::new());
+///    let mut sink: DynValueScope<dyn Write, _> = DerefScope::new(sink, |mut sink: Box<dyn Write>| {
+///        written = sink.write(b"end").unwrap();
+///    });
+///    sink.write_all(b"data").unwrap();
+///    sink.flush().unwrap();
+///}
+///assert_eq!(written, 3);
+///```
+pub type DynValueScope<T, F> = DerefScope<Box<T>, F>;
+
+impl<T: ?Sized, F: FnOnce(Box<T>)> Scope<Box<T>, F> {
+    #[inline(always)]
+    ///Creates guard over boxed unsized value, dereferencing directly to the pointee.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///
+    ///let mut len = 0;
+    ///{
+    ///    let mut buf = Scope::new_unsized(vec![0u8; 4].into_boxed_slice(), |buf: Box<[u8]>| {
+    ///        len = buf.len();
+    ///    });
+    ///    buf[0] = 1;
+    ///    assert_eq!(buf.len(), 4);
+    ///}
+    ///assert_eq!(len, 4);
+    ///```
+    pub fn new_unsized(val: Box<T>, dtor: F) -> DynValueScope<T, F> {
+        DerefScope::new(val, dtor)
+    }
+}
@@ -0,0 +1,145 @@
+use alloc::vec::Vec;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+
+use crate::Scope;
+
+///Creates guard that truncates `vec` back to its current length on drop.
+///
+///Use `forget` or `into_inner` to keep pushed elements.
+///
+///## Example
+///
+///```rust
+///use scope_guard::vec_truncate_guard;
+///
+///let mut stack = vec![1];
+///{
+///    let mut stack = vec_truncate_guard(&mut stack);
+///    stack.push(2);
+///    stack.push(3);
+///}
+///assert_eq!(stack, [1]);
+///
+///let mut stack_guard = vec_truncate_guard(&mut stack);
+///stack_guard.push(2);
+///stack_guard.forget();
+///assert_eq!(stack, [1, 2]);
+///```
+pub fn vec_truncate_guard<'a, T>(vec: &'a mut Vec<T>) -> Scope<&'a mut Vec<T>, impl FnOnce(&'a mut Vec<T>)> {
+    let len = vec.len();
+    Scope::new(vec, move |vec: &'a mut Vec<T>| vec.truncate(len))
+}
+
+///Creates guard that truncates `string` back to its current byte length on drop.
+///
+///Recorded length is always on char boundary, as it is taken from existing `String`.
+///Callers must not shrink the string below it while guard is alive, otherwise truncation panics
+///if it lands inside multi-byte character.
+///
+///## Example
+///
+///```rust
+///use scope_guard::string_truncate_guard;
+///
+///let mut buf = String::from("let");
+///{
+///    let mut buf = string_truncate_guard(&mut buf);
+///    buf.push_str(" ö = ");
+///}
+///assert_eq!(buf, "let");
+///
+///let mut buf_guard = string_truncate_guard(&mut buf);
+///buf_guard.push_str(" x");
+///buf_guard.forget();
+///assert_eq!(buf, "let x");
+///```
+pub fn string_truncate_guard<'a>(string: &'a mut String) -> Scope<&'a mut String, impl FnOnce(&'a mut String)> {
+    let len = string.len();
+    Scope::new(string, move |string: &'a mut String| string.truncate(len))
+}
+
+///Snapshots elements of `slice` at `idxs`, creating guard that restores them on drop.
+///
+///All indices are bounds-checked up front, panicking if any is out of bounds.
+///Elements are restored in reverse order of `idxs`, so duplicate index ends up with its original
+///value.
+///
+///## Example
+///
+///```rust
+///use scope_guard::indices_guard;
+///
+///let mut slots = [0, 1, 2, 3, 4];
+///{
+///    let mut slots = indices_guard(&mut slots, &[0, 2, 4]);
+///    slots[0] = 10;
+///    slots[2] = 12;
+///    slots[4] = 14;
+///}
+///assert_eq!(slots, [0, 1, 2, 3, 4]);
+///
+///{
+///    let mut slots = indices_guard(&mut slots, &[1]);
+///    slots[1] = 11;
+///    slots[3] = 13;
+///}
+///assert_eq!(slots, [0, 1, 2, 13, 4]);
+///```
+pub fn indices_guard<'a, T: Clone>(slice: &'a mut [T], idxs: &[usize]) -> Scope<&'a mut [T], impl FnOnce(&'a mut [T])> {
+    let len = slice.len();
+    for &idx in idxs {
+        assert!(idx < len, "index {} is out of bounds for slice of length {}", idx, len);
+    }
+
+    let snapshot: Vec<(usize, T)> = idxs.iter().map(|&idx| (idx, slice[idx].clone())).collect();
+    Scope::new(slice, move |slice: &'a mut [T]| {
+        for (idx, value) in snapshot.into_iter().rev() {
+            slice[idx] = value;
+        }
+    })
+}
+
+#[cfg(feature = "std")]
+///Inserts `value` under `key` and creates guard that undoes insertion on drop.
+///
+///If `key` was already present, its previous value is restored instead of removing entry.
+///Use `forget` or `into_inner` to commit insertion.
+///
+///## Example
+///
+///```rust
+///use scope_guard::map_insert_guard;
+///use std::collections::HashMap;
+///
+///let mut vars = HashMap::new();
+///vars.insert("x", 1);
+///{
+///    let vars = map_insert_guard(&mut vars, "x", 2);
+///    assert_eq!(vars["x"], 2);
+///}
+///assert_eq!(vars["x"], 1);
+///
+///{
+///    let vars = map_insert_guard(&mut vars, "y", 3);
+///    assert_eq!(vars["y"], 3);
+///}
+///assert!(!vars.contains_key("y"));
+///
+///map_insert_guard(&mut vars, "y", 3).forget();
+///assert_eq!(vars["y"], 3);
+///```
+pub fn map_insert_guard<'a, K: Hash + Eq + Clone, V>(map: &'a mut HashMap<K, V>, key: K, value: V) -> Scope<&'a mut HashMap<K, V>, impl FnOnce(&'a mut HashMap<K, V>)> {
+    let prev = map.insert(key.clone(), value);
+    Scope::new(map, move |map: &'a mut HashMap<K, V>| match prev {
+        Some(prev) => {
+            map.insert(key, prev);
+        },
+        None => {
+            map.remove(&key);
+        },
+    })
+}
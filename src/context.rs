@@ -0,0 +1,24 @@
+use crate::Scope;
+
+#[inline]
+///Creates guard, storing immutable `ctx` alongside value to be passed into `dtor`.
+///
+///Context is not accessible through guard, it is only moved into `dtor` on drop.
+///
+///## Example
+///
+///```rust
+///use scope_guard::context_guard;
+///
+///let mut log = Vec::new();
+///{
+///    let mut guard = context_guard(&mut log, "request-1", |log, id| {
+///        log.push(format!("{} done", id));
+///    });
+///    guard.push("working".to_owned());
+///}
+///assert_eq!(log, ["working", "request-1 done"]);
+///```
+pub fn context_guard<T, C, F: FnOnce(T, C)>(val: T, ctx: C, dtor: F) -> Scope<T, impl FnOnce(T)> {
+    Scope::new(val, move |val| dtor(val, ctx))
+}
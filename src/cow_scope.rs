@@ -0,0 +1,113 @@
+use core::ops::{Deref, DerefMut};
+use core::{mem, ptr};
+
+///RAII Scope over `place`, restoring it to snapshot taken before first mutation.
+///
+///Snapshot is cloned lazily on first mutable access, hence read-only use never clones value.
+///Created via [cow_guard](fn.cow_guard.html).
+pub struct CowScope<'a, T: Clone> {
+    place: &'a mut T,
+    snapshot: Option<T>,
+}
+
+impl<'a, T: Clone> CowScope<'a, T> {
+    #[inline(always)]
+    ///Returns whether value has been mutated through guard, meaning it is to be restored on drop.
+    pub fn is_dirty(&self) -> bool {
+        self.snapshot.is_some()
+    }
+
+    #[inline]
+    fn into_parts(self) -> (&'a mut T, Option<T>) {
+        let parts = unsafe {
+            (ptr::read(&self.place), ptr::read(&self.snapshot))
+        };
+        mem::forget(self);
+        parts
+    }
+
+    #[inline]
+    ///Returns underlying reference, keeping all modifications.
+    pub fn into_inner(self) -> &'a mut T {
+        self.into_parts().0
+    }
+
+    #[inline]
+    ///Forgets self, keeping all modifications.
+    pub fn forget(self) {
+        self.into_parts();
+    }
+}
+
+impl<T: Clone> Deref for CowScope<'_, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.place
+    }
+}
+
+impl<T: Clone> DerefMut for CowScope<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        if self.snapshot.is_none() {
+            self.snapshot = Some(self.place.clone());
+        }
+        self.place
+    }
+}
+
+impl<T: Clone> Drop for CowScope<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(snapshot) = self.snapshot.take() {
+            *self.place = snapshot;
+        }
+    }
+}
+
+///Creates guard that restores `place` on drop, but only if it has been mutated through guard.
+///
+///## Example
+///
+///```rust
+///use scope_guard::cow_guard;
+///use core::cell::Cell;
+///
+///struct Config<'a> {
+///    clones: &'a Cell<u32>,
+///    retries: u32,
+///}
+///
+///impl Clone for Config<'_> {
+///    fn clone(&self) -> Self {
+///        self.clones.set(self.clones.get() + 1);
+///        Self { clones: self.clones, retries: self.retries }
+///    }
+///}
+///
+///let clones = Cell::new(0);
+///let mut config = Config { clones: &clones, retries: 3 };
+///{
+///    let guard = cow_guard(&mut config);
+///    assert_eq!(guard.retries, 3);
+///    assert!(!guard.is_dirty());
+///}
+///assert_eq!(clones.get(), 0);
+///
+///{
+///    let mut guard = cow_guard(&mut config);
+///    guard.retries = 0;
+///    guard.retries += 1;
+///    assert!(guard.is_dirty());
+///}
+///assert_eq!(clones.get(), 1);
+///assert_eq!(config.retries, 3);
+///```
+pub fn cow_guard<T: Clone>(place: &mut T) -> CowScope<'_, T> {
+    CowScope {
+        place,
+        snapshot: None,
+    }
+}
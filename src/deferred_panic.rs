@@ -0,0 +1,117 @@
+use core::any::Any;
+use core::ops::{Deref, DerefMut};
+use core::{mem, ptr};
+
+use std::boxed::Box;
+
+use crate::Scope;
+
+///RAII Scope, which can be marked to panic only after its closure has run.
+///
+///This gives "finalize then fail" semantics: function can decide to panic early, while guards
+///created after this one are dropped first, followed by its own closure, after which panic is
+///resumed.
+///Pending panic is discarded if thread is already panicking.
+///
+///## Example
+///
+///```rust
+///use scope_guard::{DeferredPanic, Scope};
+///use core::cell::RefCell;
+///
+///let log = RefCell::new(Vec::new());
+///let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+///    let mut guard = DeferredPanic::new((), |_| log.borrow_mut().push("finalize"));
+///    let _inner = Scope::new((), |_| log.borrow_mut().push("inner cleanup"));
+///    guard.set_pending_panic("invariant violated");
+///    log.borrow_mut().push("body");
+///}));
+///assert_eq!(*result.unwrap_err().downcast_ref::<&str>().unwrap(), "invariant violated");
+///assert_eq!(*log.borrow(), ["body", "inner cleanup", "finalize"]);
+///```
+pub struct DeferredPanic<T, F: FnOnce(T)> {
+    scope: mem::ManuallyDrop<Scope<T, F>>,
+    pending: Option<Box<dyn Any + Send + 'static>>,
+}
+
+impl<T, F: FnOnce(T)> DeferredPanic<T, F> {
+    #[inline(always)]
+    ///Creates new instance without pending panic
+    pub fn new(val: T, dtor: F) -> Self {
+        Self {
+            scope: mem::ManuallyDrop::new(Scope::new(val, dtor)),
+            pending: None,
+        }
+    }
+
+    #[inline]
+    ///Sets `payload` to be resumed as panic after closure runs, replacing previous one.
+    pub fn set_pending_panic<P: Any + Send + 'static>(&mut self, payload: P) {
+        self.pending = Some(Box::new(payload));
+    }
+
+    #[inline]
+    ///Removes pending panic, returning its payload, if any.
+    pub fn take_pending_panic(&mut self) -> Option<Box<dyn Any + Send + 'static>> {
+        self.pending.take()
+    }
+
+    #[inline(always)]
+    ///Returns whether panic is pending.
+    pub fn is_panic_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    #[inline(always)]
+    fn into_scope(mut self) -> Scope<T, F> {
+        drop(self.pending.take());
+        let scope = unsafe {
+            ptr::read(&*self.scope)
+        };
+        mem::forget(self);
+        scope
+    }
+
+    #[inline]
+    ///Returns underlying data, without executing destructor or pending panic;
+    pub fn into_inner(self) -> T {
+        self.into_scope().into_inner()
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure and pending panic from running
+    pub fn forget(self) {
+        self.into_scope().forget()
+    }
+}
+
+impl<T, F: FnOnce(T)> Deref for DeferredPanic<T, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.scope
+    }
+}
+
+impl<T, F: FnOnce(T)> DerefMut for DeferredPanic<T, F> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.scope
+    }
+}
+
+impl<T, F: FnOnce(T)> Drop for DeferredPanic<T, F> {
+    #[inline]
+    fn drop(&mut self) {
+        let scope = unsafe {
+            ptr::read(&*self.scope)
+        };
+        drop(scope);
+        if let Some(payload) = self.pending.take() {
+            if !std::thread::panicking() {
+                crate::strategy::resume_unwind(payload);
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::Scope;
+
+///RAII Scope over smart pointer, running closure in destructor.
+///
+///Unlike `Scope<P, F>`, which dereferences to the pointer `P` itself (e.g. `Box<T>`), this guard
+///dereferences to pointee `P::Target`.
+///
+///## Example
+///
+///```rust
+///use scope_guard::DerefScope;
+///
+///struct Resource {
+///    is_open: bool,
+///}
+///
+///impl Resource {
+///    fn close(&mut self) {
+///        self.is_open = false;
+///    }
+///}
+///
+///let mut is_open = true;
+///{
+///    let mut resource = DerefScope::new(Box::new(Resource { is_open: true }), |resource| {
+///        is_open = resource.is_open;
+///    });
+///    resource.close();
+///}
+///assert!(!is_open);
+///```
+pub struct DerefScope<P, F: FnOnce(P)>(Scope<P, F>);
+
+impl<P, F: FnOnce(P)> DerefScope<P, F> {
+    #[inline(always)]
+    ///Creates new instance
+    pub fn new(ptr: P, dtor: F) -> Self {
+        Self(Scope::new(ptr, dtor))
+    }
+
+    #[inline]
+    ///Returns underlying pointer, without executing destructor;
+    pub fn into_inner(self) -> P {
+        self.0.into_inner()
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure from running
+    pub fn forget(self) {
+        self.0.forget()
+    }
+}
+
+impl<P: Deref, F: FnOnce(P)> Deref for DerefScope<P, F> {
+    type Target = P::Target;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &**self.0
+    }
+}
+
+impl<P: DerefMut, F: FnOnce(P)> DerefMut for DerefScope<P, F> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut **self.0
+    }
+}
@@ -0,0 +1,108 @@
+use core::ffi::c_void;
+use core::ops::Deref;
+
+use crate::Scope;
+
+///C cleanup function, invoked with guarded pointer.
+///
+///Matches functions declared in `extern "C"` blocks, e.g. `fn free(ptr: *mut c_void)`.
+pub type FfiDtor = unsafe extern "C" fn(*mut c_void);
+
+type FfiParts = (*mut c_void, FfiDtor);
+
+#[inline(always)]
+fn run_ffi_dtor((val, dtor): FfiParts) {
+    //Contract is upheld by caller of ffi_guard
+    unsafe {
+        dtor(val)
+    }
+}
+
+///Guard over raw pointer with C cleanup function, created by [ffi_guard](fn.ffi_guard.html).
+///
+///Dereferences only to guarded pointer, so neither pointer nor cleanup function can be replaced
+///after creation.
+pub struct FfiScope(Scope<FfiParts, fn(FfiParts)>);
+
+impl FfiScope {
+    #[inline]
+    ///Returns guarded pointer, without executing cleanup function.
+    pub fn into_inner(self) -> *mut c_void {
+        self.0.into_inner().0
+    }
+
+    #[inline]
+    ///Forgets self, preventing cleanup function from running
+    pub fn forget(self) {
+        self.0.forget()
+    }
+}
+
+impl Deref for FfiScope {
+    type Target = *mut c_void;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0.0
+    }
+}
+
+#[inline(always)]
+///Creates guard over raw pointer, invoking C cleanup function with it on drop.
+///
+///## Safety
+///
+///It must be safe to call `dtor` with `val` once, when guard is dropped, on whichever thread it
+///happens.
+///`val` must stay valid for `dtor` until then, and must not be freed elsewhere unless guard is
+///defused via `forget` or `into_inner`.
+///Guard does not dereference `val` by itself.
+///
+///## Example
+///
+///```rust
+///use scope_guard::{ffi_guard, FfiScope};
+///use core::ffi::c_void;
+///use core::sync::atomic::{AtomicBool, Ordering};
+///
+///static IS_FREED: AtomicBool = AtomicBool::new(false);
+///
+/////Stands for function declared in `extern "C"` block, e.g. `free`
+///unsafe extern "C" fn free_handle(handle: *mut c_void) {
+///    assert_eq!(handle as usize, 0xdead);
+///    IS_FREED.store(true, Ordering::SeqCst);
+///}
+///
+///struct Handle {
+///    guard: FfiScope,
+///}
+///
+///let handle = Handle {
+///    guard: unsafe {
+///        ffi_guard(0xdead as *mut c_void, free_handle)
+///    },
+///};
+///assert_eq!(*handle.guard as usize, 0xdead);
+///assert!(!IS_FREED.load(Ordering::SeqCst));
+///drop(handle);
+///assert!(IS_FREED.load(Ordering::SeqCst));
+///```
+///
+///Functions from `extern "C"` blocks are accepted as they are:
+///
+///```rust
+///use scope_guard::ffi_guard;
+///use core::ffi::c_void;
+///
+///extern "C" {
+///    fn free(ptr: *mut c_void);
+///}
+///
+///let guard = unsafe {
+///    ffi_guard(core::ptr::null_mut(), free)
+///};
+///drop(guard);
+///```
+pub unsafe fn ffi_guard(val: *mut c_void, dtor: FfiDtor) -> FfiScope {
+    FfiScope(Scope::new((val, dtor), run_ffi_dtor))
+}
@@ -0,0 +1,37 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Scope;
+
+///Sets `flag` to `value`, creating guard that restores prior value of the flag on drop.
+///
+///Prior value is captured at creation.
+///For flags, that are accessed via getter and setter, use
+///[guard_with_prev](struct.Scope.html#method.guard_with_prev) instead.
+///
+///Available only on targets with atomic read-modify-write operations on `u8`.
+///
+///## Example
+///
+///```rust
+///use scope_guard::flag_guard;
+///use core::sync::atomic::{AtomicBool, Ordering};
+///
+///static NEW_PARSER: AtomicBool = AtomicBool::new(false);
+///
+///{
+///    let _guard = flag_guard(&NEW_PARSER, true);
+///    assert!(NEW_PARSER.load(Ordering::SeqCst));
+///}
+///assert!(!NEW_PARSER.load(Ordering::SeqCst));
+///
+///let result = std::panic::catch_unwind(|| {
+///    let _guard = flag_guard(&NEW_PARSER, true);
+///    panic!("test failed");
+///});
+///assert!(result.is_err());
+///assert!(!NEW_PARSER.load(Ordering::SeqCst));
+///```
+pub fn flag_guard<'a>(flag: &'a AtomicBool, value: bool) -> Scope<&'a AtomicBool, impl FnOnce(&'a AtomicBool)> {
+    let prev = flag.swap(value, Ordering::AcqRel);
+    Scope::new(flag, move |flag: &'a AtomicBool| flag.store(prev, Ordering::Release))
+}
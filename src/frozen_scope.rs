@@ -0,0 +1,61 @@
+use core::ops::Deref;
+
+use crate::Scope;
+
+///RAII Scope that only gives immutable access to guarded value, running closure in destructor.
+///
+///Created via [freeze](struct.Scope.html#method.freeze).
+///
+///## Example
+///
+///```rust
+///use scope_guard::Scope;
+///
+///let mut is_run = false;
+///{
+///    let guard = Scope::new(1, |_| is_run = true).freeze();
+///    assert_eq!(*guard, 1);
+///}
+///assert!(is_run);
+///```
+///
+///Mutation is not possible:
+///
+///```compile_fail
+///use scope_guard::Scope;
+///
+///let mut guard = Scope::new(1, |_| ()).freeze();
+///*guard = 2;
+///```
+pub struct FrozenScope<T, F: FnOnce(T)>(Scope<T, F>);
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline(always)]
+    ///Prevents further mutation of guarded value, while keeping closure armed.
+    pub fn freeze(self) -> FrozenScope<T, F> {
+        FrozenScope(self)
+    }
+}
+
+impl<T, F: FnOnce(T)> FrozenScope<T, F> {
+    #[inline]
+    ///Returns underlying data, without executing destructor;
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure from running
+    pub fn forget(self) {
+        self.0.forget()
+    }
+}
+
+impl<T, F: FnOnce(T)> Deref for FrozenScope<T, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
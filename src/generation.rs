@@ -0,0 +1,47 @@
+use core::cell::Cell;
+
+use crate::Scope;
+
+///Creates guard, that runs `dtor` only if `generation` has not advanced since guard creation.
+///
+///Current value of `generation` is captured at creation and compared to the actual value in
+///destructor.
+///Operations that legitimately change guarded state are expected to advance `generation`, which
+///prevents outer guard from clobbering their changes with stale rollback (i.e. ABA problem).
+///
+///Value is dropped without running `dtor` if generation advanced.
+///
+///## Example
+///
+///```rust
+///use scope_guard::generation_guard;
+///use core::cell::Cell;
+///
+///let generation = Cell::new(0u64);
+///let state = Cell::new(1);
+///{
+///    let prev = state.get();
+///    let _outer = generation_guard(&generation, prev, |prev| state.set(prev));
+///    state.set(2);
+///
+///    //Inner operation commits its own change and re-stamps generation
+///    state.set(3);
+///    generation.set(generation.get() + 1);
+///}
+///assert_eq!(state.get(), 3);
+///
+///{
+///    let prev = state.get();
+///    let _outer = generation_guard(&generation, prev, |prev| state.set(prev));
+///    state.set(4);
+///}
+///assert_eq!(state.get(), 3);
+///```
+pub fn generation_guard<'a, T, F: FnOnce(T) + 'a>(generation: &'a Cell<u64>, val: T, dtor: F) -> Scope<T, impl FnOnce(T) + 'a> {
+    let stamp = generation.get();
+    Scope::new(val, move |val| {
+        if generation.get() == stamp {
+            dtor(val)
+        }
+    })
+}
@@ -0,0 +1,79 @@
+use std::io::{self, Write};
+use core::cell::Cell;
+
+use crate::Scope;
+
+///Creates guard that flushes `writer` when it goes out of scope.
+///
+///Writer is accessible through guard.
+///
+///Error of `flush` is ignored as there is no way to report it from destructor.
+///If you need to handle it, call `flush` explicitly before guard is dropped.
+///
+///## Example
+///
+///```rust
+///use scope_guard::flush_guard;
+///use std::io::{self, Write};
+///
+///struct Buffered<'a> {
+///    pending: Vec<u8>,
+///    out: &'a mut Vec<u8>,
+///}
+///
+///impl Write for Buffered<'_> {
+///    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///        self.pending.extend_from_slice(buf);
+///        Ok(buf.len())
+///    }
+///
+///    fn flush(&mut self) -> io::Result<()> {
+///        self.out.append(&mut self.pending);
+///        Ok(())
+///    }
+///}
+///
+///let mut out = Vec::new();
+///{
+///    let mut writer = flush_guard(Buffered { pending: Vec::new(), out: &mut out });
+///    writer.write_all(b"data").unwrap();
+///    assert!(writer.out.is_empty());
+///}
+///assert_eq!(out, b"data");
+///```
+pub fn flush_guard<W: Write>(writer: W) -> Scope<W, impl FnOnce(W)> {
+    Scope::new(writer, |mut writer| {
+        let _ = writer.flush();
+    })
+}
+
+///Creates guard whose fallible `dtor` reports its error into `sink` instead of panicking.
+///
+///Error is only captured, not propagated: check `sink` after guard is dropped.
+///Error of earlier cleanup, that is still in `sink`, is overwritten.
+///
+///## Example
+///
+///```rust
+///use scope_guard::io_cleanup_guard;
+///use std::io;
+///use core::cell::Cell;
+///
+///let error = Cell::new(None);
+///{
+///    let _guard = io_cleanup_guard("socket", |_| Err(io::Error::new(io::ErrorKind::BrokenPipe, "close failed")), &error);
+///}
+///assert_eq!(error.take().unwrap().kind(), io::ErrorKind::BrokenPipe);
+///
+///{
+///    let _guard = io_cleanup_guard("socket", |_| Ok(()), &error);
+///}
+///assert!(error.take().is_none());
+///```
+pub fn io_cleanup_guard<'a, T, F: FnOnce(T) -> io::Result<()> + 'a>(val: T, dtor: F, sink: &'a Cell<Option<io::Error>>) -> Scope<T, impl FnOnce(T) + 'a> {
+    Scope::new(val, move |val| {
+        if let Err(error) = dtor(val) {
+            sink.set(Some(error));
+        }
+    })
+}
@@ -0,0 +1,198 @@
+use core::cell::{Cell, OnceCell};
+use core::ops::{Deref, DerefMut};
+
+use crate::Scope;
+
+///RAII Scope over lazily initialized value, running closure in destructor only if value has been
+///initialized.
+///
+///Value is initialized on first access.
+///Created via [lazy](struct.Scope.html#method.lazy).
+///
+///## Example
+///
+///```rust
+///use scope_guard::Scope;
+///use core::cell::Cell;
+///
+///let is_init = Cell::new(false);
+///let is_run = Cell::new(false);
+///{
+///    let _guard = Scope::lazy(|| is_init.set(true), |_| is_run.set(true));
+///}
+///assert!(!is_init.get());
+///assert!(!is_run.get());
+///
+///{
+///    let mut guard = Scope::lazy(|| vec![1], |val| is_run.set(val.len() == 2));
+///    guard.push(2);
+///}
+///assert!(is_run.get());
+///```
+pub struct LazyScope<T, I: FnOnce() -> T, F: FnOnce(T)> {
+    value: OnceCell<T>,
+    init: Cell<Option<I>>,
+    dtor: Option<F>,
+}
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline(always)]
+    ///Creates new instance, deferring creation of value via `init` until first access.
+    pub fn lazy<I: FnOnce() -> T>(init: I, dtor: F) -> LazyScope<T, I, F> {
+        LazyScope {
+            value: OnceCell::new(),
+            init: Cell::new(Some(init)),
+            dtor: Some(dtor),
+        }
+    }
+}
+
+impl<T, I: FnOnce() -> T, F: FnOnce(T)> LazyScope<T, I, F> {
+    #[inline(always)]
+    ///Returns whether value has been initialized.
+    pub fn is_init(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    #[inline]
+    fn force(&self) -> &T {
+        self.value.get_or_init(|| match self.init.take() {
+            Some(init) => init(),
+            None => panic!("LazyScope initialization panicked previously"),
+        })
+    }
+}
+
+impl<T, I: FnOnce() -> T, F: FnOnce(T)> Deref for LazyScope<T, I, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.force()
+    }
+}
+
+impl<T, I: FnOnce() -> T, F: FnOnce(T)> DerefMut for LazyScope<T, I, F> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.force();
+        match self.value.get_mut() {
+            Some(value) => value,
+            None => unreachable!(),
+        }
+    }
+}
+
+impl<T, I: FnOnce() -> T, F: FnOnce(T)> Drop for LazyScope<T, I, F> {
+    #[inline]
+    fn drop(&mut self) {
+        if let (Some(value), Some(dtor)) = (self.value.take(), self.dtor.take()) {
+            dtor(value);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+///Thread-safe variant of [LazyScope](struct.LazyScope.html), backed by `std::sync::OnceLock`.
+///
+///Value can be initialized through shared reference from any thread, while closure runs in
+///destructor only if value has been initialized.
+///Created via [lazy_sync](struct.Scope.html#method.lazy_sync).
+///
+///## Example
+///
+///```rust
+///use scope_guard::Scope;
+///use std::sync::atomic::{AtomicBool, Ordering};
+///
+///let is_run = AtomicBool::new(false);
+///{
+///    let guard = Scope::lazy_sync(|| vec![1], |_| is_run.store(true, Ordering::SeqCst));
+///    std::thread::scope(|scope| {
+///        scope.spawn(|| assert!(!guard.is_init()));
+///    });
+///}
+///assert!(!is_run.load(Ordering::SeqCst));
+///
+///{
+///    let guard = Scope::lazy_sync(|| vec![1], |val| is_run.store(val.len() == 1, Ordering::SeqCst));
+///    std::thread::scope(|scope| {
+///        scope.spawn(|| assert_eq!(guard[0], 1));
+///    });
+///    assert!(guard.is_init());
+///}
+///assert!(is_run.load(Ordering::SeqCst));
+///```
+pub struct SyncLazyScope<T, I: FnOnce() -> T, F: FnOnce(T)> {
+    value: std::sync::OnceLock<T>,
+    init: std::sync::Mutex<Option<I>>,
+    dtor: Option<F>,
+}
+
+#[cfg(feature = "std")]
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline(always)]
+    ///Creates new thread-safe instance, deferring creation of value via `init` until first access.
+    pub fn lazy_sync<I: FnOnce() -> T>(init: I, dtor: F) -> SyncLazyScope<T, I, F> {
+        SyncLazyScope {
+            value: std::sync::OnceLock::new(),
+            init: std::sync::Mutex::new(Some(init)),
+            dtor: Some(dtor),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, I: FnOnce() -> T, F: FnOnce(T)> SyncLazyScope<T, I, F> {
+    #[inline(always)]
+    ///Returns whether value has been initialized.
+    pub fn is_init(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    #[inline]
+    fn force(&self) -> &T {
+        self.value.get_or_init(|| {
+            let init = match self.init.lock() {
+                Ok(mut init) => init.take(),
+                Err(error) => error.into_inner().take(),
+            };
+            match init {
+                Some(init) => init(),
+                None => panic!("SyncLazyScope initialization panicked previously"),
+            }
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, I: FnOnce() -> T, F: FnOnce(T)> Deref for SyncLazyScope<T, I, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.force()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, I: FnOnce() -> T, F: FnOnce(T)> DerefMut for SyncLazyScope<T, I, F> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.force();
+        match self.value.get_mut() {
+            Some(value) => value,
+            None => unreachable!(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, I: FnOnce() -> T, F: FnOnce(T)> Drop for SyncLazyScope<T, I, F> {
+    #[inline]
+    fn drop(&mut self) {
+        if let (Some(value), Some(dtor)) = (self.value.take(), self.dtor.take()) {
+            dtor(value);
+        }
+    }
+}
@@ -105,33 +105,338 @@
 //! assert_eq!(val, 1);
 //! assert!(is_run);
 //! ```
+//!
+//! #### Borrowing local state
+//!
+//! Closure that captures local by reference keeps it borrowed for the whole lifetime of the guard,
+//! so following code fails to compile:
+//!
+//! ```compile_fail
+//! use scope_guard::scope_guard;
+//!
+//! let mut log = Vec::new();
+//! {
+//!     let _guard = scope_guard!(|| log.push("cleanup"));
+//!     log.push("body");
+//! }
+//! ```
+//!
+//! Instead pass borrow as guarded value, so that it is accessible through the guard:
+//!
+//! ```
+//! use scope_guard::Scope;
+//!
+//! let mut log = Vec::new();
+//! {
+//!     let mut log = Scope::new_mut(&mut log, |log| log.push("cleanup"));
+//!     log.push("body");
+//! }
+//! assert_eq!(log, ["body", "cleanup"]);
+//! ```
+//!
+//! #### Persisting guarded value
+//!
+//! Guard cannot be serialized as a whole, because its closure cannot be, and deserialized guard
+//! would need new closure anyway.
+//! Instead serialize guarded value through `Deref` (e.g. `serde_json::to_vec(&*guard)`) and arm
+//! new guard over deserialized value:
+//!
+//! ```
+//! use scope_guard::Scope;
+//!
+//! let guard = Scope::new(vec![1u8, 2, 3], |_| ());
+//! //Stands for serialization of guarded value
+//! let snapshot = guard.to_vec();
+//! guard.forget();
+//!
+//! let mut restored = None;
+//! {
+//!     let guard = Scope::new(snapshot, |value| restored = Some(value));
+//!     assert_eq!(*guard, [1, 2, 3]);
+//! }
+//! assert_eq!(restored.unwrap(), [1, 2, 3]);
+//! ```
+//!
+//! #### Restoring platform state
+//!
+//! Calls, that change platform state, usually return previous state (e.g. `pthread_sigmask` for
+//! signal mask of current thread), which is to be guarded by
+//! [guard_with_prev](struct.Scope.html#method.guard_with_prev):
+//!
+//! ```
+//! use scope_guard::Scope;
+//! use core::cell::Cell;
+//!
+//! //Stand for `pthread_sigmask(SIG_BLOCK, ..)` and `pthread_sigmask(SIG_SETMASK, ..)`
+//! fn block(mask: &Cell<u32>, set: u32) -> u32 {
+//!     mask.replace(mask.get() | set)
+//! }
+//! fn set_mask(mask: &Cell<u32>, set: u32) -> u32 {
+//!     mask.replace(set)
+//! }
+//!
+//! let mask = Cell::new(0b0001);
+//! {
+//!     let _guard = Scope::guard_with_prev(|| block(&mask, 0b0100), |prev| {
+//!         set_mask(&mask, prev);
+//!     });
+//!     assert_eq!(mask.get(), 0b0101);
+//! }
+//! assert_eq!(mask.get(), 0b0001);
+//! ```
 
 #![no_std]
+#![cfg_attr(feature = "nightly", feature(try_trait_v2))]
+#![cfg_attr(feature = "nightly", feature(async_iterator))]
 #![warn(missing_docs)]
-#![cfg_attr(feature = "cargo-clippy", allow(clippy::style))]
-#![cfg_attr(feature = "cargo-clippy", allow(clippy::explicit_auto_deref))]
+#![allow(clippy::style)]
+#![allow(clippy::explicit_auto_deref)]
+
+use core::{ptr, mem, fmt};
+
+#[cfg(any(feature = "alloc", test))]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+mod boxed;
+#[cfg(feature = "alloc")]
+pub use boxed::{BoxScope, DynValueScope, forget_all, run_all};
+#[cfg(feature = "alloc")]
+mod collections;
+#[cfg(feature = "alloc")]
+pub use collections::{vec_truncate_guard, string_truncate_guard, indices_guard};
+#[cfg(feature = "std")]
+pub use collections::map_insert_guard;
+#[cfg(feature = "alloc")]
+mod undo_stack;
+#[cfg(feature = "alloc")]
+pub use undo_stack::UndoStack;
+#[cfg(feature = "alloc")]
+mod shared;
+#[cfg(feature = "alloc")]
+pub use shared::shared_guard;
+mod deref_scope;
+pub use deref_scope::DerefScope;
+mod frozen_scope;
+pub use frozen_scope::FrozenScope;
+mod lazy_scope;
+pub use lazy_scope::LazyScope;
+#[cfg(feature = "std")]
+pub use lazy_scope::SyncLazyScope;
+mod ref_scope;
+pub use ref_scope::{RefScope, FnRefScope, ref_guard};
+mod scope_mut;
+pub use scope_mut::ScopeMut;
+mod transaction;
+pub use transaction::Transaction;
+mod named_scope;
+pub use named_scope::NamedScope;
+mod armed_scope;
+pub use armed_scope::{ArmedScope, DefuseToken, ScopeRef};
+mod tracked_scope;
+pub use tracked_scope::TrackedScope;
+mod on_drop;
+pub use on_drop::OnDrop;
+mod borrow_scope;
+pub use borrow_scope::BorrowScope;
+#[cfg(feature = "nightly")]
+mod rollback;
+#[cfg(feature = "nightly")]
+pub use rollback::Rollback;
+mod array_scope;
+pub use array_scope::{ArrayScope, guard_array};
+mod reentrant;
+pub use reentrant::ReentrantCell;
+mod multi_scope;
+pub use multi_scope::MultiScope;
+mod ptr_guard;
+pub use ptr_guard::ptr_restore_guard;
+#[cfg(target_has_atomic = "8")]
+mod flag;
+#[cfg(target_has_atomic = "8")]
+pub use flag::flag_guard;
+mod generation;
+pub use generation::generation_guard;
+mod context;
+pub use context::context_guard;
+mod outcome;
+pub use outcome::{returning_guard, ran_flag_guard};
+mod ffi;
+pub use ffi::{ffi_guard, FfiDtor, FfiScope};
+mod cow_scope;
+pub use cow_scope::{CowScope, cow_guard};
+#[cfg(any(test, feature = "testutil"))]
+mod assert_runs;
+#[cfg(any(test, feature = "testutil"))]
+pub use assert_runs::{AssertRuns, RunCheck};
 
-use core::{ptr, mem};
+#[cfg(all(feature = "metrics", not(target_has_atomic = "ptr")))]
+compile_error!("`metrics` feature requires target with atomic read-modify-write operations on `usize`");
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "metrics")]
+pub use metrics::guard_drops;
 
+mod strategy;
+pub use strategy::{Strategy, Always, OnSuccess, OnUnwind};
+#[cfg(target_has_atomic = "ptr")]
+pub use strategy::set_panic_detector;
+#[cfg(feature = "std")]
+pub use strategy::PanicCount;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "std")]
+pub use io::{flush_guard, io_cleanup_guard};
+#[cfg(feature = "std")]
+mod thread;
+#[cfg(feature = "std")]
+pub use thread::{thread_local_guard, thread_local_ref_guard, join_guard};
+#[cfg(feature = "std")]
+mod deferred_panic;
+#[cfg(feature = "std")]
+pub use deferred_panic::DeferredPanic;
+#[cfg(feature = "std")]
+mod sync;
+#[cfg(feature = "std")]
+pub use sync::{unlock_guard, Unlocked, lock_pair, PairLock};
+#[cfg(feature = "std")]
+mod time;
+#[cfg(feature = "std")]
+pub use time::{min_duration_guard, timed_guard, Deadline};
 #[cfg(feature = "std")]
 mod async_scope;
 #[cfg(feature = "std")]
-pub use async_scope::{async_scope, CatchUnwindFut};
+pub use async_scope::{async_scope, async_scope_borrowed, async_scope_collect, async_scope_value, async_scope_cancellable, async_scope_hook, async_scope_retry, scope_async, CatchUnwindFut, CancelToken};
+#[cfg(all(feature = "std", feature = "nightly"))]
+pub use async_scope::CatchUnwindStream;
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub use async_scope::__block_on;
+
+#[doc(hidden)]
+//Whether this crate is built with debug assertions.
+//Doctests are always built with debug assertions, hence they cannot rely on own cfg to check
+//debug-only behavior of the crate.
+pub const __DEBUG_ASSERTIONS: bool = cfg!(debug_assertions);
+
+//Backtrace of guard creation, which is carried over by combinators.
+#[cfg(feature = "backtrace")]
+type CreationBacktrace = std::backtrace::Backtrace;
+#[cfg(not(feature = "backtrace"))]
+struct CreationBacktrace;
+
+#[cfg(feature = "backtrace")]
+#[inline(always)]
+fn capture_backtrace() -> CreationBacktrace {
+    std::backtrace::Backtrace::force_capture()
+}
+
+#[cfg(not(feature = "backtrace"))]
+#[inline(always)]
+fn capture_backtrace() -> CreationBacktrace {
+    CreationBacktrace
+}
 
 ///RAII Scope, running closure in destructor.
+///
+///## Drop order
+///
+///Value is moved into closure, hence closure decides when value is dropped: at the end of closure,
+///unless it is dropped or moved elsewhere earlier.
+///
+///If closure must always run before value's own `Drop`, use
+///[ref_guard](fn.ref_guard.html), which runs closure over `&mut T`
+///and drops value separately afterwards.
+///
+///```
+///use scope_guard::Scope;
+///use core::cell::RefCell;
+///
+///struct Value<'a>(&'a RefCell<Vec<&'static str>>);
+///
+///impl Drop for Value<'_> {
+///    fn drop(&mut self) {
+///        self.0.borrow_mut().push("value");
+///    }
+///}
+///
+///let log = RefCell::new(Vec::new());
+///
+/////Closure owns value, dropping it before finishing cleanup.
+///drop(Scope::new(Value(&log), |value| {
+///    drop(value);
+///    log.borrow_mut().push("cleanup");
+///}));
+///assert_eq!(*log.borrow(), ["value", "cleanup"]);
+///
+/////Closure only borrows value, which is dropped after cleanup.
+///log.borrow_mut().clear();
+///drop(scope_guard::ref_guard(Value(&log), |_: &mut Value| log.borrow_mut().push("cleanup")));
+///assert_eq!(*log.borrow(), ["cleanup", "value"]);
+///```
 pub struct Scope<T, F: FnOnce(T)> {
     val: mem::ManuallyDrop<T>,
-    dtor: mem::ManuallyDrop<F>
+    dtor: mem::ManuallyDrop<F>,
+    #[cfg(debug_assertions)]
+    is_run: bool,
+    #[cfg(feature = "backtrace")]
+    backtrace: mem::ManuallyDrop<std::backtrace::Backtrace>,
 }
 
 impl<T, F: FnOnce(T)> Scope<T, F> {
     #[inline(always)]
     ///Creates new instance
     pub fn new(val: T, dtor: F) -> Self {
-        Self {
-            val: mem::ManuallyDrop::new(val),
-            dtor: mem::ManuallyDrop::new(dtor),
-        }
+        Self::from_parts(val, dtor, capture_backtrace())
+    }
+
+    #[inline]
+    ///Creates new instance, snapshotting state via `save` to be passed into `restore` on drop.
+    ///
+    ///It fits getter and setter of global settings, e.g. maximum level of `log` crate:
+    ///`Scope::guard_with_prev(log::max_level, log::set_max_level)`.
+    ///Note that such settings are usually shared by whole process, hence change is visible to
+    ///other threads (e.g. concurrently running tests) until guard is dropped.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///
+    ///let state = Cell::new(1);
+    ///{
+    ///    let _guard = Scope::guard_with_prev(|| state.get(), |prev| state.set(prev));
+    ///    state.set(2);
+    ///}
+    ///assert_eq!(state.get(), 1);
+    ///```
+    pub fn guard_with_prev<S: FnOnce() -> T>(save: S, restore: F) -> Self {
+        Self::new(save(), restore)
+    }
+
+    #[inline]
+    ///Creates new instance, if `setup` succeeds.
+    ///
+    ///On failure `dtor` is dropped without being executed.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///
+    ///let is_run = Cell::new(false);
+    ///let guard = Scope::try_new(|| Err::<u32, _>("failed"), |_| is_run.set(true));
+    ///assert!(guard.is_err());
+    ///drop(guard);
+    ///assert!(!is_run.get());
+    ///
+    ///let guard = Scope::try_new(|| Ok::<_, ()>(1), |_| is_run.set(true)).unwrap();
+    ///assert_eq!(*guard, 1);
+    ///drop(guard);
+    ///assert!(is_run.get());
+    ///```
+    pub fn try_new<E, C: FnOnce() -> Result<T, E>>(setup: C, dtor: F) -> Result<Self, E> {
+        setup().map(|val| Self::new(val, dtor))
     }
 
     #[inline(always)]
@@ -148,19 +453,397 @@ impl<T, F: FnOnce(T)> Scope<T, F> {
         }
     }
 
+    #[cfg(feature = "backtrace")]
+    #[inline(always)]
+    fn drop_backtrace(&self) {
+        drop(unsafe {
+            ptr::read(&*self.backtrace)
+        });
+    }
+
+    #[inline(always)]
+    fn from_parts(val: T, dtor: F, _backtrace: CreationBacktrace) -> Self {
+        Self {
+            val: mem::ManuallyDrop::new(val),
+            dtor: mem::ManuallyDrop::new(dtor),
+            #[cfg(debug_assertions)]
+            is_run: false,
+            #[cfg(feature = "backtrace")]
+            backtrace: mem::ManuallyDrop::new(_backtrace),
+        }
+    }
+
+    #[inline(always)]
+    fn into_parts(self) -> (T, F, CreationBacktrace) {
+        let value = self.get_value();
+        let dtor = self.get_dtor();
+        #[cfg(feature = "backtrace")]
+        let backtrace = unsafe {
+            ptr::read(&*self.backtrace)
+        };
+        #[cfg(not(feature = "backtrace"))]
+        let backtrace = CreationBacktrace;
+        mem::forget(self);
+        (value, dtor, backtrace)
+    }
+
     #[inline]
     ///Returns underlying data, without executing destructor;
     pub fn into_inner(self) -> T {
-        let value = self.get_value();
-        self.forget();
-        value
+        self.into_parts().0
     }
 
     #[inline]
     ///Forgets self, preventing closure from running
     pub fn forget(self) {
-        self.get_dtor();
-        mem::forget(self);
+        let (value, _, _) = self.into_parts();
+        mem::forget(value);
+    }
+
+    #[inline]
+    ///Returns underlying data without executing closure, if `pred` is true.
+    ///
+    ///Otherwise executes closure and returns `None`.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///
+    ///let runs = Cell::new(0);
+    ///let value = Scope::new(1, |_| runs.set(runs.get() + 1)).forget_if(|val| *val == 1);
+    ///assert_eq!(value, Some(1));
+    ///assert_eq!(runs.get(), 0);
+    ///
+    ///let value = Scope::new(2, |_| runs.set(runs.get() + 1)).forget_if(|val| *val == 1);
+    ///assert_eq!(value, None);
+    ///assert_eq!(runs.get(), 1);
+    ///```
+    pub fn forget_if<P: FnOnce(&T) -> bool>(self, pred: P) -> Option<T> {
+        match pred(&self.val) {
+            true => Some(self.into_inner()),
+            false => None,
+        }
+    }
+
+    #[inline]
+    ///Returns closure without executing it, while **leaking** guarded value.
+    ///
+    ///Guarded value is never dropped, which is useful when ownership of it is transferred
+    ///outside of Rust (e.g. handed over to C code).
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///
+    ///struct Value<'a>(&'a Cell<bool>);
+    ///
+    ///impl Drop for Value<'_> {
+    ///    fn drop(&mut self) {
+    ///        self.0.set(true);
+    ///    }
+    ///}
+    ///
+    ///let is_dropped = Cell::new(false);
+    ///let is_run = Cell::new(false);
+    ///let dtor = Scope::new(Value(&is_dropped), |_| is_run.set(true)).leak_value();
+    ///assert!(!is_dropped.get());
+    ///assert!(!is_run.get());
+    ///
+    ///let is_other_dropped = Cell::new(false);
+    ///dtor(Value(&is_other_dropped));
+    ///assert!(is_run.get());
+    ///assert!(is_other_dropped.get());
+    ///assert!(!is_dropped.get());
+    ///```
+    pub fn leak_value(self) -> F {
+        let (value, dtor, _) = self.into_parts();
+        mem::forget(value);
+        dtor
+    }
+
+    #[inline]
+    ///Drops guarded value right away, returning closure without running it.
+    ///
+    ///Unlike [leak_value](#method.leak_value), value's own `Drop` is executed.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///
+    ///struct Value<'a>(&'a Cell<u32>);
+    ///
+    ///impl Drop for Value<'_> {
+    ///    fn drop(&mut self) {
+    ///        self.0.set(self.0.get() + 1);
+    ///    }
+    ///}
+    ///
+    ///let drops = Cell::new(0);
+    ///let is_run = Cell::new(false);
+    ///let dtor = Scope::new(Value(&drops), |_| is_run.set(true)).drop_value();
+    ///assert_eq!(drops.get(), 1);
+    ///assert!(!is_run.get());
+    ///
+    ///let other_drops = Cell::new(0);
+    ///dtor(Value(&other_drops));
+    ///assert!(is_run.get());
+    ///assert_eq!(drops.get(), 1);
+    ///```
+    pub fn drop_value(self) -> F {
+        self.into_parts().1
+    }
+
+    #[inline(always)]
+    ///Executes closure immediately.
+    ///
+    ///Use it to force cleanup before subsequent code that relies on it.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::RefCell;
+    ///
+    ///let log = RefCell::new(Vec::new());
+    ///let guard = Scope::new("cleanup", |val| log.borrow_mut().push(val));
+    ///guard.drop_now();
+    ///log.borrow_mut().push("after");
+    ///assert_eq!(*log.borrow(), ["cleanup", "after"]);
+    ///```
+    pub fn drop_now(self) {
+        drop(self)
+    }
+
+    #[inline]
+    ///Executes `extra` with guarded value, followed by closure immediately.
+    ///
+    ///Closure is executed even if `extra` panics.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::RefCell;
+    ///
+    ///let log = RefCell::new(Vec::new());
+    ///let guard = Scope::new("cleanup", |val| log.borrow_mut().push(val));
+    ///guard.drop_now_with(|val| {
+    ///    log.borrow_mut().push("extra");
+    ///    *val = "modified cleanup";
+    ///});
+    ///log.borrow_mut().push("after");
+    ///assert_eq!(*log.borrow(), ["extra", "modified cleanup", "after"]);
+    ///```
+    pub fn drop_now_with<E: FnOnce(&mut T)>(mut self, extra: E) {
+        extra(&mut *self.val);
+        drop(self)
+    }
+
+    #[inline(always)]
+    ///Erases whole API of the guard, returning opaque value that only executes closure on drop.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use std::any::Any;
+    ///use std::cell::Cell;
+    ///use std::rc::Rc;
+    ///
+    ///let is_run = Rc::new(Cell::new(false));
+    ///let guard = Scope::new(is_run.clone(), |is_run| is_run.set(true));
+    ///let slot: Box<dyn Any> = Box::new(guard.into_drop_only());
+    ///assert!(!is_run.get());
+    ///drop(slot);
+    ///assert!(is_run.get());
+    ///```
+    pub fn into_drop_only(self) -> impl Drop {
+        struct DropOnly<T, F: FnOnce(T)>(#[allow(unused)] Scope<T, F>);
+
+        impl<T, F: FnOnce(T)> Drop for DropOnly<T, F> {
+            #[inline(always)]
+            fn drop(&mut self) {
+            }
+        }
+
+        DropOnly(self)
+    }
+
+    #[inline]
+    ///Runs `body` with guard alive, executing closure right after `body` finishes.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///
+    ///let mut result = 0;
+    ///let len = Scope::new(Vec::new(), |val| {
+    ///    result = val.len();
+    ///}).scope(|guard| {
+    ///    guard.push(1);
+    ///    guard.push(2);
+    ///    guard.len()
+    ///});
+    ///assert_eq!(len, 2);
+    ///assert_eq!(result, 2);
+    ///```
+    pub fn scope<R, B: FnOnce(&mut Self) -> R>(mut self, body: B) -> R {
+        body(&mut self)
+    }
+
+    #[inline]
+    ///Replaces guarded value with `val`, dropping previous one right away.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::RefCell;
+    ///
+    ///struct Value<'a>(u32, &'a RefCell<Vec<String>>);
+    ///
+    ///impl Drop for Value<'_> {
+    ///    fn drop(&mut self) {
+    ///        self.1.borrow_mut().push(format!("drop {}", self.0));
+    ///    }
+    ///}
+    ///
+    ///let log = RefCell::new(Vec::new());
+    ///{
+    ///    let guard = Scope::new(Value(1, &log), |val| {
+    ///        log.borrow_mut().push(format!("dtor {}", val.0));
+    ///    });
+    ///    let _guard = guard.with_value(Value(2, &log));
+    ///    log.borrow_mut().push("replaced".to_owned());
+    ///}
+    ///assert_eq!(*log.borrow(), ["drop 1", "replaced", "dtor 2", "drop 2"]);
+    ///```
+    pub fn with_value(mut self, val: T) -> Self {
+        let old = mem::replace(&mut *self.val, val);
+        drop(old);
+        self
+    }
+}
+
+impl<'a, T: ?Sized, F: FnOnce(&'a mut T)> Scope<&'a mut T, F> {
+    #[inline(always)]
+    ///Creates new instance over mutable borrow.
+    ///
+    ///Borrow is tied to the guard, and closure's argument is always inferred as `&'a mut T`.
+    pub fn new_mut(val: &'a mut T, dtor: F) -> Self {
+        Self::new(val, dtor)
+    }
+}
+
+#[cfg(feature = "backtrace")]
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline(always)]
+    ///Returns backtrace captured at the moment of guard creation.
+    ///
+    ///Combinators, that transform guard, keep backtrace of original guard.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use std::backtrace::BacktraceStatus;
+    ///
+    ///let guard = Scope::new((), |_| ());
+    ///let backtrace = guard.creation_backtrace();
+    ///assert_eq!(backtrace.status(), BacktraceStatus::Captured);
+    ///assert!(!backtrace.to_string().is_empty());
+    ///
+    ///let expected = backtrace.to_string();
+    ///let guard = guard.stack(|_| ()).map_dtor(|dtor| dtor);
+    ///assert_eq!(guard.creation_backtrace().to_string(), expected);
+    ///```
+    pub fn creation_backtrace(&self) -> &std::backtrace::Backtrace {
+        &self.backtrace
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline]
+    ///Runs `body` with mutable access to value, catching its panic.
+    ///
+    ///Closure always runs before returning, after which either result of `body` or its panic
+    ///payload is returned. Panic of closure itself is not caught.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///
+    ///let is_run = Cell::new(false);
+    ///let result = Scope::new(vec![1], |_| is_run.set(true)).with_catch(|val| {
+    ///    val.push(2);
+    ///    panic!("body failed");
+    ///});
+    ///assert!(is_run.get());
+    ///assert_eq!(*result.unwrap_err().downcast_ref::<&str>().unwrap(), "body failed");
+    ///
+    ///let result = Scope::new(vec![1], |val| assert_eq!(val, [1, 2])).with_catch(|val| {
+    ///    val.push(2);
+    ///    val.len()
+    ///});
+    ///assert_eq!(result.unwrap(), 2);
+    ///```
+    pub fn with_catch<R, B: FnOnce(&mut T) -> R>(mut self, body: B) -> Result<R, std::boxed::Box<dyn core::any::Any + Send + 'static>> {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| body(&mut self)));
+        drop(self);
+        result
+    }
+
+    #[inline]
+    ///Makes closure best-effort, running `recover` with panic payload if closure panics.
+    ///
+    ///Panic of closure is swallowed, unless `recover` panics itself.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///
+    ///let is_recovered = Cell::new(false);
+    ///{
+    ///    let _guard = Scope::new((), |_| panic!("cleanup failed")).with_recovery(|error| {
+    ///        assert_eq!(*error.downcast_ref::<&str>().unwrap(), "cleanup failed");
+    ///        is_recovered.set(true);
+    ///    });
+    ///}
+    ///assert!(is_recovered.get());
+    ///```
+    pub fn with_recovery<R: FnOnce(std::boxed::Box<dyn core::any::Any + Send + 'static>)>(self, recover: R) -> Scope<T, impl FnOnce(T)> {
+        let (value, current_dtor, backtrace) = self.into_parts();
+        Scope::from_parts(value, move |value| {
+            if let Err(error) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| current_dtor(value))) {
+                recover(error)
+            }
+        }, backtrace)
+    }
+}
+
+impl<T> Scope<T, fn(T)> {
+    #[inline(always)]
+    ///Creates new instance with plain function pointer as destructor.
+    pub fn from_fn(val: T, dtor: fn(T)) -> Self {
+        Self::new(val, dtor)
+    }
+
+    #[inline(always)]
+    ///Returns function pointer that is going to be invoked as destructor.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///
+    ///fn cleanup(val: &mut u32) {
+    ///    *val = 0;
+    ///}
+    ///
+    ///let mut val = 1;
+    ///let guard = Scope::from_fn(&mut val, cleanup);
+    ///let dtor = guard.as_fn_ptr();
+    ///assert!(dtor == cleanup as fn(&mut u32));
+    ///dtor(guard.into_inner());
+    ///assert_eq!(val, 0);
+    ///```
+    pub fn as_fn_ptr(&self) -> fn(T) {
+        *self.dtor
+    }
+}
+
+impl<T> fmt::Pointer for Scope<T, fn(T)> {
+    #[inline(always)]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.as_fn_ptr(), fmt)
     }
 }
 
@@ -173,13 +856,130 @@ impl<T, F: FnOnce(T)> Scope<T, F> {
     ///Note that stacked function cannot take guarded by value, only original function will retain
     ///owned value.
     pub fn stack<NF: FnOnce(&mut T)>(self, dtor: NF) -> Scope<T, impl FnOnce(T)> {
-        let current_dtor = self.get_dtor();
-        let value = self.get_value();
-        mem::forget(self);
-        Scope::new(value, move |mut value| {
+        let (value, current_dtor, backtrace) = self.into_parts();
+        Scope::from_parts(value, move |mut value| {
             dtor(&mut value);
             current_dtor(value)
-        })
+        }, backtrace)
+    }
+
+    ///Adds function to observe guarded value, right before closure is executed.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///
+    ///let observed = Cell::new(0);
+    ///let consumed = Cell::new(0);
+    ///{
+    ///    let mut guard = Scope::new(1, |val| {
+    ///        assert_eq!(observed.get(), val);
+    ///        consumed.set(val);
+    ///    }).inspect_on_drop(|val| observed.set(*val));
+    ///    *guard = 2;
+    ///    assert_eq!(observed.get(), 0);
+    ///}
+    ///assert_eq!(observed.get(), 2);
+    ///assert_eq!(consumed.get(), 2);
+    ///```
+    pub fn inspect_on_drop<I: FnOnce(&T)>(self, inspect: I) -> Scope<T, impl FnOnce(T)> {
+        let (value, current_dtor, backtrace) = self.into_parts();
+        Scope::from_parts(value, move |value| {
+            inspect(&value);
+            current_dtor(value)
+        }, backtrace)
+    }
+
+    ///Combines two guards into one, with value created out of both values via `join`.
+    ///
+    ///On drop value is split back via `split` and both closures are executed.
+    ///Closure of `other` is executed first, similarly to [stack](#method.stack).
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::RefCell;
+    ///
+    ///struct Pair {
+    ///    num: u32,
+    ///    text: &'static str,
+    ///}
+    ///
+    ///let log = RefCell::new(Vec::new());
+    ///{
+    ///    let num = Scope::new(1, |num| log.borrow_mut().push(format!("num {}", num)));
+    ///    let text = Scope::new("text", |text| log.borrow_mut().push(format!("text {}", text)));
+    ///    let mut pair = num.zip_with(text, |num, text| Pair { num, text }, |pair| (pair.num, pair.text));
+    ///    pair.num += 1;
+    ///    assert_eq!(pair.text, "text");
+    ///}
+    ///assert_eq!(*log.borrow(), ["text text", "num 2"]);
+    ///```
+    pub fn zip_with<B, FB: FnOnce(B), R, J: FnOnce(T, B) -> R, S: FnOnce(R) -> (T, B)>(self, other: Scope<B, FB>, join: J, split: S) -> Scope<R, impl FnOnce(R)> {
+        let (value, current_dtor, backtrace) = self.into_parts();
+        let (other_value, other_dtor, _) = other.into_parts();
+        Scope::from_parts(join(value, other_value), move |value| {
+            let (value, other_value) = split(value);
+            other_dtor(other_value);
+            current_dtor(value)
+        }, backtrace)
+    }
+
+    #[inline]
+    ///Transforms closure via `map`, without running it.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///
+    ///let counter = &Cell::new(0);
+    ///let result = Cell::new(0);
+    ///{
+    ///    let _guard = Scope::new(1, |val| result.set(val)).map_dtor(|dtor| {
+    ///        move |val| {
+    ///            counter.set(counter.get() + 1);
+    ///            dtor(val)
+    ///        }
+    ///    });
+    ///    assert_eq!(counter.get(), 0);
+    ///}
+    ///assert_eq!(counter.get(), 1);
+    ///assert_eq!(result.get(), 1);
+    ///```
+    pub fn map_dtor<NF: FnOnce(T), M: FnOnce(F) -> NF>(self, map: M) -> Scope<T, NF> {
+        let (value, dtor, backtrace) = self.into_parts();
+        Scope::from_parts(value, map(dtor), backtrace)
+    }
+
+    #[inline]
+    ///Transforms value via `to`, while closure still receives original value reconstructed via
+    ///`from` on drop.
+    ///
+    ///Transformation is expected to be bijective, i.e. `from(to(x)) == x`, otherwise closure
+    ///observes different value.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///
+    ///struct Port(u32);
+    ///
+    ///impl Port {
+    ///    fn next(&mut self) {
+    ///        self.0 += 1;
+    ///    }
+    ///}
+    ///
+    ///let released = Cell::new(0);
+    ///{
+    ///    let mut port = Scope::new(8080, |port: u32| released.set(port)).map_bijective(Port, |port| port.0);
+    ///    port.next();
+    ///    assert_eq!(port.0, 8081);
+    ///}
+    ///assert_eq!(released.get(), 8081);
+    ///```
+    pub fn map_bijective<U, TO: FnOnce(T) -> U, FROM: FnOnce(U) -> T>(self, to: TO, from: FROM) -> Scope<U, impl FnOnce(U)> {
+        let (value, dtor, backtrace) = self.into_parts();
+        Scope::from_parts(to(value), move |value| dtor(from(value)), backtrace)
     }
 }
 
@@ -197,9 +997,115 @@ impl<T, F: FnOnce(T)> core::ops::DerefMut for Scope<T, F> {
     }
 }
 
+impl<T: fmt::Debug, F: FnOnce(T)> fmt::Debug for Scope<T, F> {
+    ///Formats guarded value.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///
+    ///let guard = Scope::new(1, |_| ());
+    ///assert_eq!(format!("{:?}", guard), "Scope { value: 1 }");
+    ///```
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Scope").field("value", &*self.val).finish()
+    }
+}
+
 impl<T, F: FnOnce(T)> Drop for Scope<T, F> {
     #[inline(always)]
+    ///Runs closure with guarded value.
+    ///
+    ///In debug builds it panics if the same guard is dropped twice, which can only happen due to
+    ///incorrect `unsafe` code.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///
+    ///if scope_guard::__DEBUG_ASSERTIONS {
+    ///    let result = std::panic::catch_unwind(|| {
+    ///        let mut guard = core::mem::ManuallyDrop::new(Scope::new((), |_| ()));
+    ///        unsafe {
+    ///            core::mem::ManuallyDrop::drop(&mut guard);
+    ///            core::mem::ManuallyDrop::drop(&mut guard);
+    ///        }
+    ///    });
+    ///    assert!(result.is_err());
+    ///}
+    ///```
+    ///
+    ///## Panic safety
+    ///
+    ///Guarded value is moved into closure, hence it is dropped exactly once: either by closure
+    ///or during unwinding if closure panics midway.
+    ///Closure runs exactly once, including when guard is dropped due to panic.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use core::cell::Cell;
+    ///use std::panic::{catch_unwind, AssertUnwindSafe};
+    ///
+    ///struct Value<'a>(&'a Cell<u32>);
+    ///
+    ///impl Drop for Value<'_> {
+    ///    fn drop(&mut self) {
+    ///        self.0.set(self.0.get() + 1);
+    ///    }
+    ///}
+    ///
+    ///let drops = Cell::new(0);
+    ///let runs = Cell::new(0);
+    ///
+    /////Closure panics midway, before consuming value.
+    ///let result = catch_unwind(AssertUnwindSafe(|| {
+    ///    let _guard = Scope::new(Value(&drops), |_value| {
+    ///        runs.set(runs.get() + 1);
+    ///        panic!("cleanup failed");
+    ///    });
+    ///}));
+    ///assert!(result.is_err());
+    ///assert_eq!(runs.get(), 1);
+    ///assert_eq!(drops.get(), 1);
+    ///
+    /////Body panics, closure consumes value during unwinding.
+    ///let result = catch_unwind(AssertUnwindSafe(|| {
+    ///    let _guard = Scope::new(Value(&drops), |value| {
+    ///        runs.set(runs.get() + 1);
+    ///        drop(value);
+    ///    });
+    ///    panic!("body failed");
+    ///}));
+    ///assert!(result.is_err());
+    ///assert_eq!(runs.get(), 2);
+    ///assert_eq!(drops.get(), 2);
+    ///
+    /////Reclaimed value is dropped by its owner only.
+    ///let value = Scope::new(Value(&drops), |_| runs.set(runs.get() + 1)).into_inner();
+    ///drop(value);
+    ///assert_eq!(runs.get(), 2);
+    ///assert_eq!(drops.get(), 3);
+    ///
+    /////Stacked closures only borrow value, so it is dropped even if one of them panics.
+    ///let result = catch_unwind(AssertUnwindSafe(|| {
+    ///    let _guard = Scope::new(Value(&drops), |_| runs.set(runs.get() + 1))
+    ///        .stack(|_| panic!("inner cleanup failed"))
+    ///        .inspect_on_drop(|_| runs.set(runs.get() + 1));
+    ///}));
+    ///assert!(result.is_err());
+    ///assert_eq!(runs.get(), 3);
+    ///assert_eq!(drops.get(), 4);
+    ///```
     fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        {
+            assert!(!self.is_run, "Scope closure is invoked second time");
+            self.is_run = true;
+        }
+        #[cfg(feature = "backtrace")]
+        self.drop_backtrace();
+
+        #[cfg(feature = "metrics")]
+        metrics::record_guard_drop();
+
         let val = self.get_value();
         let func = self.get_dtor();
         func(val);
@@ -220,3 +1126,106 @@ macro_rules! scope_guard {
         $crate::Scope::new(($($args),+), $dtor)
     };
 }
+
+#[macro_export]
+///Swaps field of the struct with temporary value, restoring original value on drop.
+///
+///Guard holds mutable reference to the field, through which it can be accessed until guard is
+///dropped.
+///
+///```
+///use scope_guard::swap_field_guard;
+///
+///struct Config {
+///    verbose: bool,
+///    retries: u32,
+///}
+///
+///let mut config = Config { verbose: false, retries: 3 };
+///{
+///    let mut retries = swap_field_guard!(config.retries, 0);
+///    assert_eq!(**retries, 0);
+///    **retries += 1;
+///}
+///assert_eq!(config.retries, 3);
+///
+///let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+///    let _verbose = swap_field_guard!(config.verbose, true);
+///    panic!("fail");
+///}));
+///assert!(result.is_err());
+///assert!(!config.verbose);
+///```
+macro_rules! swap_field_guard {
+    ($target:ident $(.$field:ident)+, $temp:expr) => {{
+        let field = &mut $target$(.$field)+;
+        let prev = ::core::mem::replace(field, $temp);
+        $crate::Scope::new(field, move |field| *field = prev)
+    }};
+}
+
+#[macro_export]
+///Composes multiple guards into single one, executing their closures in reverse order.
+///
+///Values are combined into nested pairs, with first guard's value being the innermost:
+///`((first, second), third)`.
+///
+///```
+///use scope_guard::{compose_guards, Scope};
+///use core::cell::RefCell;
+///
+///let log = RefCell::new(Vec::new());
+///{
+///    let guard = compose_guards![
+///        Scope::new(1, |val| log.borrow_mut().push(val)),
+///        Scope::new(2, |val| log.borrow_mut().push(val)),
+///        Scope::new(3, |val| log.borrow_mut().push(val)),
+///    ];
+///    assert_eq!(*guard, ((1, 2), 3));
+///}
+///assert_eq!(*log.borrow(), [3, 2, 1]);
+///
+///log.borrow_mut().clear();
+///compose_guards![
+///    Scope::new(1, |val| log.borrow_mut().push(val)),
+///    Scope::new(2, |val| log.borrow_mut().push(val)),
+///].forget();
+///assert!(log.borrow().is_empty());
+///```
+macro_rules! compose_guards {
+    ($guard:expr $(,)?) => {
+        $guard
+    };
+    ($first:expr, $second:expr $(, $rest:expr)* $(,)?) => {
+        $crate::compose_guards!($first.zip_with($second, |first, second| (first, second), |pair| pair) $(, $rest)*)
+    };
+}
+
+//Locks in size of the guard, which must be only size of value and closure, with exception of
+//debug bookkeeping and diagnostic features.
+#[cfg(not(feature = "backtrace"))]
+const _: () = {
+    const fn scope_size<T, F: FnOnce(T)>(_: &F) -> usize {
+        mem::size_of::<Scope<T, F>>()
+    }
+
+    #[cfg(debug_assertions)]
+    const fn expected_size<T, F>() -> usize {
+        mem::size_of::<(T, F, bool)>()
+    }
+
+    #[cfg(not(debug_assertions))]
+    const fn expected_size<T, F>() -> usize {
+        mem::size_of::<(T, F)>()
+    }
+
+    let noop = |_: ()| {};
+    assert!(scope_size(&noop) == expected_size::<(), ()>());
+    #[cfg(not(debug_assertions))]
+    assert!(scope_size(&noop) == 0);
+
+    assert!(mem::size_of::<Scope<u32, fn(u32)>>() == expected_size::<u32, fn(u32)>());
+    #[cfg(target_pointer_width = "64")]
+    assert!(mem::size_of::<Scope<u32, fn(u32)>>() == 2 * mem::size_of::<usize>());
+    assert!(mem::size_of::<Scope<u64, fn(u64)>>() == expected_size::<u64, fn(u64)>());
+};
@@ -0,0 +1,41 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+static GUARD_DROPS: AtomicUsize = AtomicUsize::new(0);
+
+#[inline(always)]
+pub(crate) fn record_guard_drop() {
+    GUARD_DROPS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[inline(always)]
+///Returns number of times [Scope](struct.Scope.html) has been dropped with its closure armed in
+///whole process.
+///
+///This is not number of executed user closures: guards, whose strategy declines to run wrapped
+///closure (e.g. [OnSuccess](struct.OnSuccess.html) on unwind), are counted too.
+///Guards that are defused (e.g. via `forget` or `into_inner`) are not counted.
+///Available with `metrics` feature, without which no counting is performed.
+///Feature requires target with atomic read-modify-write operations on `usize`.
+///
+///## Example
+///
+///```rust
+///use scope_guard::{guard_drops, Scope, Strategy};
+///
+///struct Never;
+///
+///impl Strategy for Never {
+///    fn should_run(&self) -> bool {
+///        false
+///    }
+///}
+///
+///let before = guard_drops();
+///drop(Scope::new((), |_| ()));
+///drop(Scope::with_strategy((), |_| (), Never));
+///Scope::new((), |_| ()).forget();
+///assert_eq!(guard_drops() - before, 2);
+///```
+pub fn guard_drops() -> usize {
+    GUARD_DROPS.load(Ordering::Relaxed)
+}
@@ -0,0 +1,81 @@
+use crate::Scope;
+
+///Collection of independent guards, each running its own closure against its own value.
+///
+///Closures are executed in reverse order of registration, similarly to how stack variables dtors
+///are invoked.
+///
+///## Example
+///
+///```rust
+///use scope_guard::MultiScope;
+///use core::cell::{Cell, RefCell};
+///
+///let num = Cell::new(1);
+///let text = Cell::new("original");
+///let log = RefCell::new(Vec::new());
+///{
+///    let _guard = MultiScope::new().guard(&num, |num| {
+///        num.set(1);
+///        log.borrow_mut().push("num");
+///    }).guard(&text, |text| {
+///        text.set("original");
+///        log.borrow_mut().push("text");
+///    });
+///    num.set(2);
+///    text.set("changed");
+///}
+///assert_eq!(num.get(), 1);
+///assert_eq!(text.get(), "original");
+///assert_eq!(*log.borrow(), ["text", "num"]);
+///```
+pub struct MultiScope<L>(L);
+
+impl MultiScope<()> {
+    #[inline(always)]
+    ///Creates new empty instance
+    pub const fn new() -> Self {
+        Self(())
+    }
+}
+
+impl Default for MultiScope<()> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L> MultiScope<L> {
+    #[inline(always)]
+    ///Adds new guard over `val`, executing `dtor` before all previously added guards.
+    pub fn guard<T, F: FnOnce(T)>(self, val: T, dtor: F) -> MultiScope<(Scope<T, F>, L)> {
+        MultiScope((Scope::new(val, dtor), self.0))
+    }
+}
+
+#[macro_export]
+///Creates [MultiScope](struct.MultiScope.html) out of pairs `(value, closure)`.
+///
+///```rust
+///use scope_guard::multi_scope_guard;
+///use core::cell::Cell;
+///
+///let first = Cell::new(1);
+///let second = Cell::new(2);
+///{
+///    let _guard = multi_scope_guard!(
+///        (&first, |val: &Cell<u32>| val.set(1)),
+///        (&second, |val: &Cell<u32>| val.set(2)),
+///    );
+///    first.set(0);
+///    second.set(0);
+///}
+///assert_eq!(first.get(), 1);
+///assert_eq!(second.get(), 2);
+///```
+macro_rules! multi_scope_guard {
+    ($(($val:expr, $dtor:expr)),+ $(,)?) => {
+        $crate::MultiScope::new()$(.guard($val, $dtor))+
+    };
+}
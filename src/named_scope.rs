@@ -0,0 +1,76 @@
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::Scope;
+
+///RAII Scope with static label for diagnostics, running closure in destructor.
+///
+///Label is included in `Debug` output.
+///Created via [named](struct.Scope.html#method.named).
+///
+///## Example
+///
+///```rust
+///use scope_guard::Scope;
+///
+///let guard = Scope::named("restore_config", 1, |_| ());
+///assert_eq!(guard.name(), "restore_config");
+///assert_eq!(format!("{:?}", guard), "Scope { name: \"restore_config\", value: 1 }");
+///```
+pub struct NamedScope<T, F: FnOnce(T)> {
+    name: &'static str,
+    scope: Scope<T, F>,
+}
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline(always)]
+    ///Creates new instance with static label `name`.
+    pub fn named(name: &'static str, val: T, dtor: F) -> NamedScope<T, F> {
+        NamedScope {
+            name,
+            scope: Scope::new(val, dtor),
+        }
+    }
+}
+
+impl<T, F: FnOnce(T)> NamedScope<T, F> {
+    #[inline(always)]
+    ///Returns label of the guard.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    #[inline]
+    ///Returns underlying data, without executing destructor;
+    pub fn into_inner(self) -> T {
+        self.scope.into_inner()
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure from running
+    pub fn forget(self) {
+        self.scope.forget()
+    }
+}
+
+impl<T, F: FnOnce(T)> Deref for NamedScope<T, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.scope
+    }
+}
+
+impl<T, F: FnOnce(T)> DerefMut for NamedScope<T, F> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.scope
+    }
+}
+
+impl<T: fmt::Debug, F: FnOnce(T)> fmt::Debug for NamedScope<T, F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Scope").field("name", &self.name).field("value", &*self.scope).finish()
+    }
+}
@@ -0,0 +1,56 @@
+use core::{mem, ptr};
+
+///Minimal RAII guard, running closure without arguments in destructor.
+///
+///It can be created in `const` context, e.g. over plain function pointer.
+///Note that `static` is never dropped, hence guard must be copied out of `const` to be useful.
+///
+///## Example
+///
+///```rust
+///use scope_guard::OnDrop;
+///use core::sync::atomic::{AtomicBool, Ordering};
+///
+///static IS_RUN: AtomicBool = AtomicBool::new(false);
+///
+///fn cleanup() {
+///    IS_RUN.store(true, Ordering::Relaxed);
+///}
+///
+///const CLEANUP: OnDrop<fn()> = OnDrop::new(cleanup);
+///
+///{
+///    let _guard = CLEANUP;
+///    assert!(!IS_RUN.load(Ordering::Relaxed));
+///}
+///assert!(IS_RUN.load(Ordering::Relaxed));
+///```
+pub struct OnDrop<F: FnOnce()>(mem::ManuallyDrop<F>);
+
+impl<F: FnOnce()> OnDrop<F> {
+    #[inline(always)]
+    ///Creates new instance
+    pub const fn new(dtor: F) -> Self {
+        Self(mem::ManuallyDrop::new(dtor))
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure from running
+    pub fn forget(self) {
+        let dtor = unsafe {
+            ptr::read(&*self.0)
+        };
+        mem::forget(self);
+        drop(dtor);
+    }
+}
+
+impl<F: FnOnce()> Drop for OnDrop<F> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        let dtor = unsafe {
+            ptr::read(&*self.0)
+        };
+        dtor();
+    }
+}
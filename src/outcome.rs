@@ -0,0 +1,51 @@
+use core::cell::Cell;
+
+use crate::Scope;
+
+#[inline]
+///Creates guard, storing result of `dtor` into `out` on drop.
+///
+///Allows to observe outcome of cleanup, when guard itself is owned elsewhere.
+///
+///## Example
+///
+///```rust
+///use scope_guard::returning_guard;
+///use core::cell::Cell;
+///
+///let flushed = Cell::new(None);
+///{
+///    let mut guard = returning_guard(Vec::new(), &flushed, |buf: Vec<u8>| buf.len());
+///    guard.extend_from_slice(b"data");
+///    assert_eq!(flushed.get(), None);
+///}
+///assert_eq!(flushed.get(), Some(4));
+///```
+pub fn returning_guard<'a, T, R, F: FnOnce(T) -> R + 'a>(val: T, out: &'a Cell<Option<R>>, dtor: F) -> Scope<T, impl FnOnce(T) + 'a> {
+    Scope::new(val, move |val| out.set(Some(dtor(val))))
+}
+
+#[inline]
+///Creates guard, setting `flag` to `true` right after `dtor` runs on drop.
+///
+///`flag` is left untouched if guard is defused.
+///
+///## Example
+///
+///```rust
+///use scope_guard::ran_flag_guard;
+///use core::cell::Cell;
+///
+///let is_run = Cell::new(false);
+///ran_flag_guard(1, |_| (), &is_run).forget();
+///assert!(!is_run.get());
+///
+///drop(ran_flag_guard(1, |_| (), &is_run));
+///assert!(is_run.get());
+///```
+pub fn ran_flag_guard<'a, T, F: FnOnce(T) + 'a>(val: T, dtor: F, flag: &'a Cell<bool>) -> Scope<T, impl FnOnce(T) + 'a> {
+    Scope::new(val, move |val| {
+        dtor(val);
+        flag.set(true);
+    })
+}
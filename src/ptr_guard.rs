@@ -0,0 +1,55 @@
+use core::ptr;
+
+use crate::Scope;
+
+///Replaces value at `dst` with `tmp`, creating guard that restores original value on drop.
+///
+///Value at `dst` on drop (i.e. `tmp` or whatever was written over it) is dropped after restoration.
+///This is intended for low-level code, swapping entries of global pointer tables (e.g. interrupt
+///vectors) in `no_std` environment.
+///
+///## Safety
+///
+///`dst` must be valid for reads and writes, properly aligned and point to initialized value of `T`
+///for the whole lifetime of the guard.
+///No other code may access `dst` concurrently with creation or drop of the guard.
+///
+///## Example
+///
+///```rust
+///use scope_guard::ptr_restore_guard;
+///
+///fn default_handler() -> u32 {
+///    0
+///}
+///
+///fn test_handler() -> u32 {
+///    1
+///}
+///
+///static mut HANDLER: fn() -> u32 = default_handler;
+///
+///unsafe {
+///    let handler = core::ptr::addr_of_mut!(HANDLER);
+///    {
+///        let _guard = ptr_restore_guard(handler, test_handler as fn() -> u32);
+///        assert_eq!((*handler)(), 1);
+///    }
+///    assert_eq!((*handler)(), 0);
+///}
+///
+///let mut first = 1u8;
+///let mut second = 2u8;
+///let mut table: *mut u8 = &mut first;
+///unsafe {
+///    let _guard = ptr_restore_guard(&mut table, &mut second as *mut u8);
+///    assert_eq!(*table, 2);
+///}
+///assert_eq!(unsafe { *table }, 1);
+///```
+pub unsafe fn ptr_restore_guard<T>(dst: *mut T, tmp: T) -> Scope<*mut T, impl FnOnce(*mut T)> {
+    let prev = ptr::replace(dst, tmp);
+    Scope::new(dst, move |dst: *mut T| unsafe {
+        drop(ptr::replace(dst, prev));
+    })
+}
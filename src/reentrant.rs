@@ -0,0 +1,93 @@
+use core::cell::Cell;
+
+use crate::Scope;
+
+///Place for value that can be overridden by nested guards, restoring only original value.
+///
+///Each guard created via [set](#method.set) is stamped with unique generation.
+///Only the outermost guard holds snapshot of original value and restores it, while nested guards
+///(e.g. created recursively) are no-op on drop, preventing restore of intermediate values.
+///
+///Guards may be dropped in any order: once the outermost guard restores original value, the next
+///created guard becomes the outermost one.
+///
+///## Example
+///
+///```rust
+///use scope_guard::ReentrantCell;
+///
+///fn recurse(place: &ReentrantCell<u32>, depth: u32) {
+///    let _guard = place.set(depth);
+///    if depth > 0 {
+///        recurse(place, depth - 1);
+///        //Nested guard doesn't restore intermediate value
+///        assert_eq!(place.get(), 0);
+///    }
+///}
+///
+///let place = ReentrantCell::new(100);
+///recurse(&place, 3);
+///assert_eq!(place.get(), 100);
+///
+/////Outermost guard dropped before nested one
+///let outer = place.set(1);
+///let inner = place.set(2);
+///drop(outer);
+///assert_eq!(place.get(), 100);
+///drop(inner);
+///assert_eq!(place.get(), 100);
+///
+///{
+///    let _guard = place.set(7);
+///}
+///assert_eq!(place.get(), 100);
+///```
+pub struct ReentrantCell<T> {
+    value: Cell<T>,
+    //Last issued generation
+    generation: Cell<usize>,
+    //Generation of the outermost guard, or 0 if there is none
+    outermost: Cell<usize>,
+}
+
+impl<T> ReentrantCell<T> {
+    #[inline(always)]
+    ///Creates new instance
+    pub const fn new(val: T) -> Self {
+        Self {
+            value: Cell::new(val),
+            generation: Cell::new(0),
+            outermost: Cell::new(0),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns copy of current value.
+    pub fn get(&self) -> T where T: Copy {
+        self.value.get()
+    }
+
+    ///Sets new value, returning guard that restores original value if it is the outermost one.
+    pub fn set<'a>(&'a self, val: T) -> Scope<&'a Self, impl FnOnce(&'a Self) + 'a> {
+        let generation = self.generation.get().wrapping_add(1).max(1);
+        self.generation.set(generation);
+
+        let prev = self.value.replace(val);
+        let snapshot = match self.outermost.get() {
+            0 => {
+                self.outermost.set(generation);
+                Some(prev)
+            },
+            _ => None,
+        };
+
+        Scope::new(self, move |this| {
+            if let Some(prev) = snapshot {
+                if this.outermost.get() == generation {
+                    this.outermost.set(0);
+                    this.value.set(prev);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use core::{mem, ptr};
+use core::ops::{Deref, DerefMut};
+
+///RAII Scope, running closure over mutable reference to value in destructor.
+///
+///Unlike [Scope](struct.Scope.html), closure never takes ownership of the value, so value is
+///always dropped after closure finishes and can be reclaimed with cleanup applied via
+///[into_inner_and_run](#method.into_inner_and_run).
+pub struct RefScope<T, F: FnOnce(&mut T)> {
+    val: mem::ManuallyDrop<T>,
+    dtor: mem::ManuallyDrop<F>,
+}
+
+///Reference taking guard with plain function pointer as destructor, suitable for struct fields.
+pub type FnRefScope<T> = RefScope<T, fn(&mut T)>;
+
+#[inline(always)]
+///Creates guard whose closure takes value by mutable reference, instead of consuming it.
+///
+///Value is dropped after closure finishes, or can be reclaimed via `into_inner` or
+///`into_inner_and_run`.
+///
+///## Example
+///
+///```rust
+///use scope_guard::{ref_guard, FnRefScope};
+///
+///fn reset(val: &mut Vec<u32>) {
+///    val.clear();
+///}
+///
+///let mut guard = ref_guard(vec![1], |val: &mut Vec<u32>| val.push(3));
+///guard.push(2);
+///assert_eq!(guard.into_inner_and_run(), [1, 2, 3]);
+///
+///let guard: FnRefScope<Vec<u32>> = ref_guard(vec![1], reset);
+///assert_eq!(guard.into_inner_and_run(), []);
+///
+///let guard = ref_guard(vec![1], |val: &mut Vec<u32>| val.clear());
+///assert_eq!(guard.into_inner(), [1]);
+///```
+pub fn ref_guard<T, F: FnOnce(&mut T)>(val: T, dtor: F) -> RefScope<T, F> {
+    RefScope::new(val, dtor)
+}
+
+impl<T, F: FnOnce(&mut T)> RefScope<T, F> {
+    #[inline(always)]
+    ///Creates new instance
+    pub fn new(val: T, dtor: F) -> Self {
+        Self {
+            val: mem::ManuallyDrop::new(val),
+            dtor: mem::ManuallyDrop::new(dtor),
+        }
+    }
+
+    #[inline(always)]
+    fn into_parts(self) -> (T, F) {
+        let parts = unsafe {
+            (ptr::read(&*self.val), ptr::read(&*self.dtor))
+        };
+        mem::forget(self);
+        parts
+    }
+
+    #[inline]
+    ///Returns underlying data, without executing destructor;
+    pub fn into_inner(self) -> T {
+        self.into_parts().0
+    }
+
+    #[inline]
+    ///Executes closure, returning underlying data afterwards.
+    ///
+    ///```
+    ///use scope_guard::RefScope;
+    ///
+    ///let guard = RefScope::new(vec![1], |val| val.push(2));
+    ///assert_eq!(guard.into_inner_and_run(), [1, 2]);
+    ///```
+    pub fn into_inner_and_run(self) -> T {
+        let (mut value, dtor) = self.into_parts();
+        dtor(&mut value);
+        value
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure from running
+    pub fn forget(self) {
+        let (value, _) = self.into_parts();
+        mem::forget(value);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, F: FnOnce(&mut T)> RefScope<T, F> {
+    #[inline]
+    ///Executes closure immediately, catching its panic.
+    ///
+    ///Returns underlying data on success, otherwise drops it and returns panic payload.
+    ///
+    ///```
+    ///use scope_guard::RefScope;
+    ///
+    ///let guard = RefScope::new(vec![1], |val| val.push(2));
+    ///assert_eq!(guard.run_catching().unwrap(), [1, 2]);
+    ///
+    ///let guard = RefScope::new(vec![1], |_| panic!("cleanup failed"));
+    ///let error = guard.run_catching().unwrap_err();
+    ///assert_eq!(*error.downcast_ref::<&str>().unwrap(), "cleanup failed");
+    ///```
+    pub fn run_catching(self) -> Result<T, std::boxed::Box<dyn core::any::Any + Send + 'static>> {
+        let (mut value, dtor) = self.into_parts();
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| dtor(&mut value))).map(|_| value)
+    }
+}
+
+impl<T, F: FnOnce(&mut T)> Deref for RefScope<T, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.val
+    }
+}
+
+impl<T, F: FnOnce(&mut T)> DerefMut for RefScope<T, F> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.val
+    }
+}
+
+impl<T, F: FnOnce(&mut T)> Drop for RefScope<T, F> {
+    #[inline(always)]
+    ///Runs closure over value, dropping value afterwards.
+    ///
+    ///Value is dropped exactly once even if closure panics.
+    ///
+    ///```
+    ///use scope_guard::RefScope;
+    ///use core::cell::Cell;
+    ///
+    ///struct Value<'a>(&'a Cell<u32>);
+    ///
+    ///impl Drop for Value<'_> {
+    ///    fn drop(&mut self) {
+    ///        self.0.set(self.0.get() + 1);
+    ///    }
+    ///}
+    ///
+    ///let drops = Cell::new(0);
+    ///let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    ///    let _guard = RefScope::new(Value(&drops), |_| panic!("cleanup failed"));
+    ///}));
+    ///assert!(result.is_err());
+    ///assert_eq!(drops.get(), 1);
+    ///```
+    fn drop(&mut self) {
+        let (mut value, func) = unsafe {
+            (ptr::read(&*self.val), ptr::read(&*self.dtor))
+        };
+        func(&mut value);
+    }
+}
@@ -0,0 +1,92 @@
+use core::convert::Infallible;
+use core::ops::{ControlFlow, FromResidual, Try};
+
+use crate::Transaction;
+
+///Result of operation, which turns successful value into armed [Transaction](struct.Transaction.html)
+///when unwrapped via `?`.
+///
+///Available only on nightly with `nightly` feature.
+///
+///On success `?` yields `Transaction`, which runs `rollback` with the value on drop, unless it is
+///committed. Hence if later `?` propagates error, every previously succeeded step is rolled back
+///in reverse order.
+///On failure there is nothing to roll back, so `rollback` is dropped without running.
+///
+///## Example
+///
+///```rust
+///use scope_guard::Rollback;
+///use core::cell::RefCell;
+///
+///fn run(log: &RefCell<Vec<&'static str>>, is_fail: bool) -> Result<u32, &'static str> {
+///    let first = Rollback::new(Ok(1), |_| log.borrow_mut().push("rollback first"))?;
+///    let second = match is_fail {
+///        true => Err("fail"),
+///        false => Ok(2),
+///    };
+///    let second = Rollback::new(second, |_| log.borrow_mut().push("rollback second"))?;
+///    Ok(first.commit() + second.commit())
+///}
+///
+///let log = RefCell::new(Vec::new());
+///assert_eq!(run(&log, false), Ok(3));
+///assert!(log.borrow().is_empty());
+///
+///assert_eq!(run(&log, true), Err("fail"));
+///assert_eq!(*log.borrow(), ["rollback first"]);
+///```
+#[must_use]
+pub struct Rollback<T, E, F: FnOnce(T)> {
+    result: Result<Transaction<T, F>, E>,
+}
+
+impl<T, E, F: FnOnce(T)> Rollback<T, E, F> {
+    #[inline(always)]
+    ///Creates new instance
+    pub fn new(result: Result<T, E>, rollback: F) -> Self {
+        Self {
+            result: result.map(|val| Transaction::new(val, rollback)),
+        }
+    }
+
+    #[inline]
+    ///Returns underlying result, without executing `rollback`.
+    pub fn into_result(self) -> Result<T, E> {
+        self.result.map(Transaction::commit)
+    }
+}
+
+impl<T, E, F: FnOnce(T)> Try for Rollback<T, E, F> {
+    type Output = Transaction<T, F>;
+    type Residual = Result<Infallible, E>;
+
+    #[inline(always)]
+    fn from_output(output: Self::Output) -> Self {
+        Self {
+            result: Ok(output),
+        }
+    }
+
+    #[inline]
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+        match self.result {
+            Ok(transaction) => ControlFlow::Continue(transaction),
+            Err(error) => ControlFlow::Break(Err(error)),
+        }
+    }
+}
+
+impl<T, E, F: FnOnce(T)> FromResidual<Result<Infallible, E>> for Rollback<T, E, F> {
+    #[inline(always)]
+    fn from_residual(residual: Result<Infallible, E>) -> Self {
+        let error = match residual {
+            Ok(never) => match never {},
+            Err(error) => error,
+        };
+
+        Self {
+            result: Err(error),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use core::{mem, ptr};
+use core::ops::{Deref, DerefMut};
+
+///RAII Scope, running closure in destructor, as well as on demand via [run](#method.run).
+///
+///Closure is `FnMut`, so it can be invoked multiple times (e.g. to flush value periodically).
+///
+///## Example
+///
+///```rust
+///use scope_guard::ScopeMut;
+///use core::cell::Cell;
+///
+///let flushed = Cell::new(0);
+///{
+///    let mut guard = ScopeMut::new(0, |val: &mut u32| {
+///        flushed.set(flushed.get() + *val);
+///        *val = 0;
+///    });
+///    *guard = 1;
+///    guard.run();
+///    *guard = 2;
+///    guard.run();
+///    assert_eq!(flushed.get(), 3);
+///    *guard = 3;
+///}
+///assert_eq!(flushed.get(), 6);
+///```
+pub struct ScopeMut<T, F: FnMut(&mut T)> {
+    val: T,
+    dtor: F,
+    #[cfg(any(test, feature = "testutil"))]
+    runs: usize,
+}
+
+impl<T, F: FnMut(&mut T)> ScopeMut<T, F> {
+    #[inline(always)]
+    ///Creates new instance
+    pub fn new(val: T, dtor: F) -> Self {
+        Self {
+            val,
+            dtor,
+            #[cfg(any(test, feature = "testutil"))]
+            runs: 0,
+        }
+    }
+
+    #[inline]
+    ///Executes closure over value, keeping guard armed.
+    pub fn run(&mut self) {
+        #[cfg(any(test, feature = "testutil"))]
+        {
+            self.runs += 1;
+        }
+        (self.dtor)(&mut self.val)
+    }
+
+    #[cfg(any(test, feature = "testutil"))]
+    #[inline(always)]
+    ///Returns number of times closure has been executed via [run](#method.run).
+    ///
+    ///Available with `testutil` feature, without which no counting is performed.
+    ///
+    ///```
+    ///use scope_guard::ScopeMut;
+    ///use core::cell::Cell;
+    ///
+    ///let runs = Cell::new(0);
+    ///{
+    ///    let mut guard = ScopeMut::new((), |_: &mut ()| runs.set(runs.get() + 1));
+    ///    assert_eq!(guard.run_count(), 0);
+    ///    guard.run();
+    ///    guard.run();
+    ///    assert_eq!(guard.run_count(), 2);
+    ///}
+    /////Run on drop is not included into count
+    ///assert_eq!(runs.get(), 3);
+    ///```
+    pub fn run_count(&self) -> usize {
+        self.runs
+    }
+
+    #[inline(always)]
+    ///Returns reference to closure.
+    pub fn dtor_ref(&self) -> &F {
+        &self.dtor
+    }
+
+    #[inline(always)]
+    fn into_parts(self) -> (T, F) {
+        let parts = unsafe {
+            (ptr::read(&self.val), ptr::read(&self.dtor))
+        };
+        mem::forget(self);
+        parts
+    }
+
+    #[inline]
+    ///Returns underlying data, without executing destructor;
+    pub fn into_inner(self) -> T {
+        self.into_parts().0
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure from running
+    pub fn forget(self) {
+        let (value, _) = self.into_parts();
+        mem::forget(value);
+    }
+}
+
+impl<T, F: FnMut(&mut T)> Deref for ScopeMut<T, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.val
+    }
+}
+
+impl<T, F: FnMut(&mut T)> DerefMut for ScopeMut<T, F> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.val
+    }
+}
+
+impl<T, F: FnMut(&mut T)> Drop for ScopeMut<T, F> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        (self.dtor)(&mut self.val)
+    }
+}
@@ -0,0 +1,31 @@
+use core::cell::RefCell;
+
+use alloc::rc::Rc;
+
+use crate::Scope;
+
+///Creates guard over state shared by multiple owners, passing guard's handle to `dtor` on drop.
+///
+///Plain `Scope` owns its value, hence other code cannot access it while guard is alive.
+///Sharing state via `Rc<RefCell<T>>` allows other owners to keep mutating it, while cleanup still
+///runs when guard goes out of scope.
+///Guard dereferences to `Rc`, so same handle can be cloned for other owners too.
+///
+///## Example
+///
+///```rust
+///use scope_guard::shared_guard;
+///use std::cell::RefCell;
+///use std::rc::Rc;
+///
+///let state = Rc::new(RefCell::new(Vec::new()));
+///{
+///    let guard = shared_guard(state.clone(), |state| state.borrow_mut().push("cleanup"));
+///    let handler = Rc::clone(&guard);
+///    handler.borrow_mut().push("event");
+///}
+///assert_eq!(*state.borrow(), ["event", "cleanup"]);
+///```
+pub fn shared_guard<T, F: FnOnce(Rc<RefCell<T>>)>(shared: Rc<RefCell<T>>, dtor: F) -> Scope<Rc<RefCell<T>>, F> {
+    Scope::new(shared, dtor)
+}
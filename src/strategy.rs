@@ -0,0 +1,377 @@
+#[cfg(target_has_atomic = "ptr")]
+use core::mem;
+#[cfg(target_has_atomic = "ptr")]
+use core::ptr;
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::Scope;
+
+#[cfg(target_has_atomic = "ptr")]
+static PANIC_DETECTOR: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+#[cfg(target_has_atomic = "ptr")]
+///Sets function to detect whether current thread is panicking.
+///
+///It is used by guards that run closure only on success or only on unwind.
+///By default `std::thread::panicking()` is used when `std` feature is enabled, otherwise
+///panic state is unknown and such guards never run closure.
+///
+///## Contract
+///
+///`detector` must return `true` only while current thread is unwinding due to panic (e.g. by
+///checking counter incremented in your `#[panic_handler]`), and it must be safe to call from any
+///thread and within `Drop`.
+///Once set, `detector` is used instead of `std::thread::panicking()`.
+///
+///Available only on targets with pointer-sized atomics.
+///
+///## Example
+///
+///```rust
+///use scope_guard::{Scope, set_panic_detector};
+///use core::sync::atomic::{AtomicBool, Ordering};
+///
+///static IS_PANICKING: AtomicBool = AtomicBool::new(false);
+///
+///fn is_panicking() -> bool {
+///    IS_PANICKING.load(Ordering::Relaxed)
+///}
+///
+///set_panic_detector(is_panicking);
+///
+///let mut is_unwind = false;
+///let guard = Scope::new((), |_| ()).on_unwind_also(|_| is_unwind = true);
+///IS_PANICKING.store(true, Ordering::Relaxed);
+///drop(guard);
+///assert!(is_unwind);
+///```
+pub fn set_panic_detector(detector: fn() -> bool) {
+    PANIC_DETECTOR.store(detector as *mut (), Ordering::Release);
+}
+
+#[cfg(target_has_atomic = "ptr")]
+#[inline(always)]
+fn custom_detector() -> Option<bool> {
+    let detector = PANIC_DETECTOR.load(Ordering::Acquire);
+    if detector.is_null() {
+        return None;
+    }
+
+    let detector = unsafe {
+        mem::transmute::<*mut (), fn() -> bool>(detector)
+    };
+    Some(detector())
+}
+
+#[cfg(not(target_has_atomic = "ptr"))]
+#[inline(always)]
+fn custom_detector() -> Option<bool> {
+    None
+}
+
+#[inline(always)]
+///Returns whether current thread is panicking, or `None` if it cannot be detected.
+pub(crate) fn is_panicking() -> Option<bool> {
+    if let Some(is_panicking) = custom_detector() {
+        return Some(is_panicking);
+    }
+
+    #[cfg(feature = "std")]
+    {
+        Some(std::thread::panicking())
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        None
+    }
+}
+
+///Strategy deciding whether guard's closure should run on drop.
+///
+///## Example
+///
+///```rust
+///use scope_guard::{Scope, Strategy};
+///use core::cell::Cell;
+///
+///struct EveryEven<'a>(&'a Cell<u32>);
+///
+///impl Strategy for EveryEven<'_> {
+///    fn should_run(&self) -> bool {
+///        self.0.set(self.0.get() + 1);
+///        self.0.get() % 2 == 0
+///    }
+///}
+///
+///let drops = Cell::new(0);
+///let runs = Cell::new(0);
+///for _ in 0..4 {
+///    let _guard = Scope::with_strategy((), |_| runs.set(runs.get() + 1), EveryEven(&drops));
+///}
+///assert_eq!(drops.get(), 4);
+///assert_eq!(runs.get(), 2);
+///```
+pub trait Strategy {
+    ///Returns whether closure should run, invoked once when guard is dropped.
+    fn should_run(&self) -> bool;
+}
+
+///Strategy to always run closure.
+pub struct Always;
+
+impl Strategy for Always {
+    #[inline(always)]
+    fn should_run(&self) -> bool {
+        true
+    }
+}
+
+///Strategy to run closure only when scope exits normally.
+///
+///Panic is detected as described in [set_panic_detector](fn.set_panic_detector.html).
+pub struct OnSuccess;
+
+impl Strategy for OnSuccess {
+    #[inline(always)]
+    fn should_run(&self) -> bool {
+        is_panicking() == Some(false)
+    }
+}
+
+///Strategy to run closure only when scope exits due to panic.
+///
+///Panic is detected as described in [set_panic_detector](fn.set_panic_detector.html).
+pub struct OnUnwind;
+
+impl Strategy for OnUnwind {
+    #[inline(always)]
+    fn should_run(&self) -> bool {
+        is_panicking() == Some(true)
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static PANIC_COUNT: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+#[cfg(feature = "std")]
+static PANIC_HOOK: std::sync::Once = std::sync::Once::new();
+
+#[cfg(feature = "std")]
+#[cold]
+///Resumes unwinding, counting it as panic for [PanicCount](struct.PanicCount.html).
+pub(crate) fn resume_unwind(payload: std::boxed::Box<dyn core::any::Any + Send>) -> ! {
+    let _ = PANIC_COUNT.try_with(|count| count.set(count.get() + 1));
+    std::panic::resume_unwind(payload)
+}
+
+#[cfg(feature = "std")]
+///Strategy to run closure only when scope exits due to panic, that started after strategy has
+///been created.
+///
+///**Note:** first creation of strategy installs process-wide panic hook, chaining previously
+///installed hook.
+///If panic hook is replaced afterwards without chaining it, panics are no longer counted and
+///closure never runs.
+///
+///Unlike [OnUnwind](struct.OnUnwind.html), it does not misfire when guard is created and dropped
+///normally within destructor that runs during unwinding of unrelated panic.
+///
+///Panics are counted per thread.
+///Unwinding started via `std::panic::resume_unwind` does not invoke panic hook, hence it is not
+///counted unless initiated by this crate (e.g. [DeferredPanic](struct.DeferredPanic.html) or
+///[join_guard](fn.join_guard.html)).
+///
+///## Example
+///
+///```rust
+///use scope_guard::{Scope, OnUnwind, PanicCount};
+///use core::cell::Cell;
+///use std::panic::{catch_unwind, AssertUnwindSafe};
+///
+///struct Rollback<'a> {
+///    on_unwind: &'a Cell<bool>,
+///    panic_count: &'a Cell<bool>,
+///}
+///
+///impl Drop for Rollback<'_> {
+///    fn drop(&mut self) {
+///        //Cleanup exits normally, while outer panic is still unwinding
+///        let _on_unwind = Scope::with_strategy((), |_| self.on_unwind.set(true), OnUnwind);
+///        let _panic_count = Scope::with_strategy((), |_| self.panic_count.set(true), PanicCount::new());
+///    }
+///}
+///
+///let on_unwind = Cell::new(false);
+///let panic_count = Cell::new(false);
+///let result = catch_unwind(AssertUnwindSafe(|| {
+///    let _rollback = Rollback { on_unwind: &on_unwind, panic_count: &panic_count };
+///    panic!("outer");
+///}));
+///assert!(result.is_err());
+///assert!(on_unwind.get());
+///assert!(!panic_count.get());
+///
+/////Caught inner panic is not relevant to the guard
+///let is_run = Cell::new(false);
+///{
+///    let _guard = Scope::with_strategy((), |_| is_run.set(true), PanicCount::new());
+///    assert!(catch_unwind(|| panic!("inner")).is_err());
+///}
+///assert!(!is_run.get());
+///
+///let result = catch_unwind(AssertUnwindSafe(|| {
+///    let _guard = Scope::with_strategy((), |_| is_run.set(true), PanicCount::new());
+///    panic!("relevant");
+///}));
+///assert!(result.is_err());
+///assert!(is_run.get());
+///
+/////Panic of joined thread is propagated by crate, hence it is counted
+///is_run.set(false);
+///let result = catch_unwind(AssertUnwindSafe(|| {
+///    let _guard = Scope::with_strategy((), |_| is_run.set(true), PanicCount::new());
+///    let _join = scope_guard::join_guard(std::thread::spawn(|| panic!("thread")));
+///}));
+///assert!(result.is_err());
+///assert!(is_run.get());
+///```
+pub struct PanicCount {
+    count: usize,
+}
+
+#[cfg(feature = "std")]
+impl PanicCount {
+    #[inline]
+    ///Creates new instance, capturing number of panics that happened on current thread so far.
+    pub fn new() -> Self {
+        //Hook cannot be set while panicking, in which case it is installed by next instance.
+        if !std::thread::panicking() {
+            PANIC_HOOK.call_once(|| {
+                let prev = std::panic::take_hook();
+                std::panic::set_hook(std::boxed::Box::new(move |info| {
+                    let _ = PANIC_COUNT.try_with(|count| count.set(count.get() + 1));
+                    prev(info)
+                }));
+            });
+        }
+
+        Self {
+            count: PANIC_COUNT.try_with(|count| count.get()).unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for PanicCount {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Strategy for PanicCount {
+    #[inline(always)]
+    fn should_run(&self) -> bool {
+        std::thread::panicking() && PANIC_COUNT.try_with(|count| count.get() > self.count).unwrap_or(false)
+    }
+}
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline]
+    ///Creates new instance, which runs `dtor` only if `strategy` allows it.
+    pub fn with_strategy<S: Strategy>(val: T, dtor: F, strategy: S) -> Scope<T, impl FnOnce(T)> {
+        Scope::new(val, move |val| {
+            if strategy.should_run() {
+                dtor(val)
+            }
+        })
+    }
+
+    ///Adds new function to be invoked in scope of the guard, only if scope exits normally.
+    ///
+    ///Panic is detected as described in [set_panic_detector](fn.set_panic_detector.html).
+    ///
+    ///Similarly to [stack](struct.Scope.html#method.stack), this function is executed before
+    ///current one.
+    ///
+    ///Example requires `std` feature, as there is no panic detector otherwise.
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    ///use scope_guard::Scope;
+    ///
+    ///fn run(log: &mut Vec<&'static str>, is_panic: bool) {
+    ///    let _guard = Scope::new(log, |log| {
+    ///        log.push("always");
+    ///    }).on_success_also(|log| {
+    ///        log.push("success");
+    ///    });
+    ///    if is_panic {
+    ///        panic!("unwind");
+    ///    }
+    ///}
+    ///
+    ///let mut log = Vec::new();
+    ///run(&mut log, false);
+    ///assert_eq!(log, ["success", "always"]);
+    ///
+    ///let mut log = Vec::new();
+    ///let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| run(&mut log, true)));
+    ///assert_eq!(log, ["always"]);
+    ///```
+    pub fn on_success_also<NF: FnOnce(&mut T)>(self, dtor: NF) -> Scope<T, impl FnOnce(T)> {
+        let (value, current_dtor, backtrace) = self.into_parts();
+        Scope::from_parts(value, move |mut value| {
+            if OnSuccess.should_run() {
+                dtor(&mut value);
+            }
+            current_dtor(value)
+        }, backtrace)
+    }
+
+    ///Adds new function to be invoked in scope of the guard, only if scope exits due to panic.
+    ///
+    ///Panic is detected as described in [set_panic_detector](fn.set_panic_detector.html).
+    ///
+    ///Similarly to [stack](struct.Scope.html#method.stack), this function is executed before
+    ///current one.
+    ///
+    ///Example requires `std` feature, as there is no panic detector otherwise.
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    ///use scope_guard::Scope;
+    ///
+    ///fn run(log: &mut Vec<&'static str>, is_panic: bool) {
+    ///    let _guard = Scope::new(log, |log| {
+    ///        log.push("always");
+    ///    }).on_unwind_also(|log| {
+    ///        log.push("unwind");
+    ///    });
+    ///    if is_panic {
+    ///        panic!("unwind");
+    ///    }
+    ///}
+    ///
+    ///let mut log = Vec::new();
+    ///run(&mut log, false);
+    ///assert_eq!(log, ["always"]);
+    ///
+    ///let mut log = Vec::new();
+    ///let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| run(&mut log, true)));
+    ///assert_eq!(log, ["unwind", "always"]);
+    ///```
+    pub fn on_unwind_also<NF: FnOnce(&mut T)>(self, dtor: NF) -> Scope<T, impl FnOnce(T)> {
+        let (value, current_dtor, backtrace) = self.into_parts();
+        Scope::from_parts(value, move |mut value| {
+            if OnUnwind.should_run() {
+                dtor(&mut value);
+            }
+            current_dtor(value)
+        }, backtrace)
+    }
+}
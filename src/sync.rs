@@ -0,0 +1,158 @@
+use core::mem;
+use std::sync::{LockResult, Mutex, MutexGuard, PoisonError};
+
+#[must_use = "Lock is released, call relock() to acquire it again"]
+///Temporarily released lock of `Mutex`, created via [unlock_guard](fn.unlock_guard.html).
+pub struct Unlocked<'a, T: ?Sized> {
+    mutex: &'a Mutex<T>,
+}
+
+impl<'a, T: ?Sized> Unlocked<'a, T> {
+    #[inline]
+    ///Acquires lock again, returning fresh guard.
+    ///
+    ///Poisoning is propagated as it is, same as with `Mutex::lock`.
+    pub fn relock(self) -> LockResult<MutexGuard<'a, T>> {
+        self.mutex.lock()
+    }
+}
+
+///Releases lock held by `guard` of `mutex`, allowing other threads to use it during nested
+///blocking operation.
+///
+///Lock is acquired again via [relock](struct.Unlocked.html#method.relock), so that caller resumes
+///with valid guard.
+///
+///`guard` must belong to `mutex`, otherwise `relock` acquires unrelated lock.
+///
+///## Example
+///
+///```rust
+///use scope_guard::unlock_guard;
+///use std::sync::Mutex;
+///
+///let state = Mutex::new(vec![1]);
+///let mut guard = state.lock().unwrap();
+///guard.push(2);
+///
+///let unlocked = unlock_guard(&state, guard);
+///std::thread::scope(|scope| {
+///    scope.spawn(|| state.lock().unwrap().push(3));
+///});
+///let guard = unlocked.relock().unwrap();
+///assert_eq!(*guard, [1, 2, 3]);
+///```
+pub fn unlock_guard<'a, T: ?Sized>(mutex: &'a Mutex<T>, guard: MutexGuard<'a, T>) -> Unlocked<'a, T> {
+    drop(guard);
+    Unlocked {
+        mutex,
+    }
+}
+
+///Guard over two locks, acquired in canonical order and released in reverse order.
+///
+///Created via [lock_pair](fn.lock_pair.html).
+pub struct PairLock<'a, A, B> {
+    a: mem::ManuallyDrop<MutexGuard<'a, A>>,
+    b: mem::ManuallyDrop<MutexGuard<'a, B>>,
+    is_a_first: bool,
+}
+
+impl<A, B> PairLock<'_, A, B> {
+    #[inline(always)]
+    ///Returns references to both guarded values.
+    pub fn get(&self) -> (&A, &B) {
+        (&self.a, &self.b)
+    }
+
+    #[inline(always)]
+    ///Returns mutable references to both guarded values.
+    pub fn get_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.a, &mut self.b)
+    }
+}
+
+impl<A, B> Drop for PairLock<'_, A, B> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            if self.is_a_first {
+                mem::ManuallyDrop::drop(&mut self.b);
+                mem::ManuallyDrop::drop(&mut self.a);
+            } else {
+                mem::ManuallyDrop::drop(&mut self.a);
+                mem::ManuallyDrop::drop(&mut self.b);
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn recover<G>(result: LockResult<G>, is_poisoned: &mut bool) -> G {
+    result.unwrap_or_else(|error| {
+        *is_poisoned = true;
+        error.into_inner()
+    })
+}
+
+///Locks both `a` and `b` in canonical order (by address), regardless of order of arguments.
+///
+///As long as every code path locks the same pair via this function, they cannot deadlock each
+///other.
+///Locks are released in reverse order of acquisition when guard is dropped.
+///
+///Returns `Err` if any of mutexes is poisoned, from which guard still can be recovered.
+///
+///## Panics
+///
+///If `a` and `b` are the same mutex.
+///
+///## Example
+///
+///```rust
+///use scope_guard::lock_pair;
+///use std::sync::Mutex;
+///
+///let from = Mutex::new(10);
+///let to = Mutex::new(0);
+///{
+///    let mut pair = lock_pair(&from, &to).unwrap();
+///    let (from, to) = pair.get_mut();
+///    *from -= 5;
+///    *to += 5;
+///}
+///{
+///    let pair = lock_pair(&to, &from).unwrap();
+///    assert_eq!(pair.get(), (&5, &5));
+///}
+///assert_eq!(*from.lock().unwrap(), 5);
+///assert_eq!(*to.lock().unwrap(), 5);
+///```
+pub fn lock_pair<'a, A, B>(a: &'a Mutex<A>, b: &'a Mutex<B>) -> LockResult<PairLock<'a, A, B>> {
+    let a_addr = a as *const Mutex<A> as usize;
+    let b_addr = b as *const Mutex<B> as usize;
+    assert_ne!(a_addr, b_addr, "Cannot lock the same mutex twice");
+
+    let mut is_poisoned = false;
+    let is_a_first = a_addr < b_addr;
+    let pair = if is_a_first {
+        let a = recover(a.lock(), &mut is_poisoned);
+        PairLock {
+            a: mem::ManuallyDrop::new(a),
+            b: mem::ManuallyDrop::new(recover(b.lock(), &mut is_poisoned)),
+            is_a_first,
+        }
+    } else {
+        let b = recover(b.lock(), &mut is_poisoned);
+        PairLock {
+            a: mem::ManuallyDrop::new(recover(a.lock(), &mut is_poisoned)),
+            b: mem::ManuallyDrop::new(b),
+            is_a_first,
+        }
+    };
+
+    match is_poisoned {
+        false => Ok(pair),
+        true => Err(PoisonError::new(pair)),
+    }
+}
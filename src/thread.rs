@@ -0,0 +1,135 @@
+use std::cell::{Cell, RefCell};
+use std::thread::{self, JoinHandle, LocalKey};
+
+use crate::Scope;
+
+///Sets thread local `key` to `val`, returning guard that restores previous value on drop.
+///
+///Guarded value is previous value of `key`.
+///
+///Restore is skipped if thread local is already destroyed (i.e. guard dropped during thread
+///teardown).
+///
+///## Example
+///
+///```rust
+///use scope_guard::thread_local_guard;
+///use std::cell::Cell;
+///
+///thread_local! {
+///    static CONTEXT: Cell<u32> = Cell::new(0);
+///}
+///
+///{
+///    let prev = thread_local_guard(&CONTEXT, 1);
+///    assert_eq!(*prev, 0);
+///    assert_eq!(CONTEXT.with(Cell::get), 1);
+///}
+///assert_eq!(CONTEXT.with(Cell::get), 0);
+///```
+pub fn thread_local_guard<T: 'static>(key: &'static LocalKey<Cell<T>>, val: T) -> Scope<T, impl FnOnce(T)> {
+    let prev = key.with(|cell| cell.replace(val));
+    Scope::new(prev, move |prev| {
+        let _ = key.try_with(|cell| cell.set(prev));
+    })
+}
+
+///Sets thread local `key` to `val`, returning guard that restores previous value on drop.
+///
+///Guarded value is previous value of `key`.
+///
+///Restore is skipped if thread local is already destroyed (i.e. guard dropped during thread
+///teardown).
+///
+///## Panics
+///
+///If thread local is currently borrowed.
+///
+///## Example
+///
+///```rust
+///use scope_guard::thread_local_ref_guard;
+///use std::cell::RefCell;
+///
+///thread_local! {
+///    static CONTEXT: RefCell<String> = RefCell::new(String::new());
+///}
+///
+///let result = std::panic::catch_unwind(|| {
+///    let _prev = thread_local_ref_guard(&CONTEXT, "request".to_owned());
+///    assert_eq!(CONTEXT.with(|ctx| ctx.borrow().clone()), "request");
+///    panic!("fail");
+///});
+///assert!(result.is_err());
+///assert_eq!(CONTEXT.with(|ctx| ctx.borrow().clone()), "");
+///```
+pub fn thread_local_ref_guard<T: 'static>(key: &'static LocalKey<RefCell<T>>, val: T) -> Scope<T, impl FnOnce(T)> {
+    let prev = key.with(|cell| cell.replace(val));
+    Scope::new(prev, move |prev| {
+        let _ = key.try_with(|cell| cell.replace(prev));
+    })
+}
+
+///Creates guard that joins thread on drop.
+///
+///If joined thread panicked, its panic is propagated to current thread, unless current thread is
+///already panicking, in which case it is ignored to avoid abort.
+///
+///## Example
+///
+///```rust
+///use scope_guard::join_guard;
+///use std::sync::atomic::{AtomicBool, Ordering};
+///use std::sync::Arc;
+///
+///let is_done = Arc::new(AtomicBool::new(false));
+///{
+///    let is_done = is_done.clone();
+///    let _guard = join_guard(std::thread::spawn(move || {
+///        std::thread::sleep(std::time::Duration::from_millis(10));
+///        is_done.store(true, Ordering::Release);
+///    }));
+///}
+///assert!(is_done.load(Ordering::Acquire));
+///```
+pub fn join_guard<T>(handle: JoinHandle<T>) -> Scope<JoinHandle<T>, impl FnOnce(JoinHandle<T>)> {
+    Scope::new(handle, |handle: JoinHandle<T>| {
+        if let Err(error) = handle.join() {
+            if !thread::panicking() {
+                crate::strategy::resume_unwind(error);
+            }
+        }
+    })
+}
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline]
+    ///Makes guard assert that it is dropped on the same thread it was created on.
+    ///
+    ///In debug builds, dropping guard on different thread panics without running closure.
+    ///In release builds check is compiled out and closure is returned as it is.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///
+    ///let guard = Scope::new((), |_| ()).with_thread_check();
+    ///let result = std::thread::spawn(move || drop(guard)).join();
+    ///assert_eq!(result.is_err(), scope_guard::__DEBUG_ASSERTIONS);
+    ///
+    ///drop(Scope::new((), |_| ()).with_thread_check());
+    ///```
+    pub fn with_thread_check(self) -> Scope<T, impl FnOnce(T)> {
+        #[cfg(debug_assertions)]
+        {
+            let owner = thread::current().id();
+            self.map_dtor(move |dtor| move |val| {
+                assert_eq!(thread::current().id(), owner, "Scope is dropped on different thread than it was created on");
+                dtor(val)
+            })
+        }
+        #[cfg(not(debug_assertions))]
+        {
+            self
+        }
+    }
+}
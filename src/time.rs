@@ -0,0 +1,104 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{Scope, OnSuccess, Strategy};
+
+///Creates guard that ensures scope lasts at least `min` duration.
+///
+///Guarded value is `Instant` of guard creation, and on drop current thread sleeps for remaining
+///time, if any.
+///
+///Sleep is skipped when scope exits due to panic, in order not to delay unwinding.
+///
+///## Example
+///
+///```rust
+///use scope_guard::min_duration_guard;
+///use std::time::{Duration, Instant};
+///
+///let start = Instant::now();
+///{
+///    let _guard = min_duration_guard(Duration::from_millis(20));
+///}
+///assert!(start.elapsed() >= Duration::from_millis(20));
+///```
+pub fn min_duration_guard(min: Duration) -> Scope<Instant, impl FnOnce(Instant)> {
+    Scope::with_strategy(Instant::now(), move |start: Instant| {
+        if let Some(remaining) = min.checked_sub(start.elapsed()) {
+            thread::sleep(remaining);
+        }
+    }, OnSuccess)
+}
+
+///Creates guard that measures duration of scope, passing it to `report` on drop.
+///
+///Guarded value is `Instant` of guard creation.
+///
+///## Example
+///
+///```rust
+///use scope_guard::timed_guard;
+///use std::time::Duration;
+///
+///let mut total = Duration::default();
+///{
+///    let guard = timed_guard(|elapsed| total = elapsed);
+///    let first = guard.elapsed();
+///    std::thread::sleep(Duration::from_millis(1));
+///    let second = guard.elapsed();
+///    assert!(second > first);
+///}
+///assert!(total >= Duration::from_millis(1));
+///```
+pub fn timed_guard<F: FnOnce(Duration)>(report: F) -> Scope<Instant, impl FnOnce(Instant)> {
+    Scope::new(Instant::now(), move |start: Instant| {
+        report(start.elapsed())
+    })
+}
+
+impl<F: FnOnce(Instant)> Scope<Instant, F> {
+    #[inline(always)]
+    ///Returns time elapsed since guarded `Instant`.
+    pub fn elapsed(&self) -> Duration {
+        self.val.elapsed()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+///Strategy to run closure only if it is dropped strictly before deadline.
+pub struct Deadline(pub Instant);
+
+impl Strategy for Deadline {
+    #[inline(always)]
+    fn should_run(&self) -> bool {
+        Instant::now() < self.0
+    }
+}
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline]
+    ///Creates new instance, which runs `dtor` only if dropped strictly before `deadline`.
+    ///
+    ///Once `deadline` is reached, guard is treated as expired and value is dropped without running
+    ///`dtor`, regardless of whether scope exits normally or due to panic.
+    ///Time is checked once, at the moment of drop.
+    ///
+    ///```
+    ///use scope_guard::Scope;
+    ///use std::time::{Duration, Instant};
+    ///
+    ///let mut is_run = false;
+    ///let deadline = Instant::now() + Duration::from_secs(3600);
+    ///drop(Scope::guard_until((), |_| is_run = true, deadline));
+    ///assert!(is_run);
+    ///
+    ///let mut is_run = false;
+    ///let guard = Scope::guard_until((), |_| is_run = true, Instant::now());
+    ///std::thread::sleep(Duration::from_millis(1));
+    ///drop(guard);
+    ///assert!(!is_run);
+    ///```
+    pub fn guard_until(val: T, dtor: F, deadline: Instant) -> Scope<T, impl FnOnce(T)> {
+        Scope::with_strategy(val, dtor, Deadline(deadline))
+    }
+}
@@ -0,0 +1,73 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::Scope;
+
+///RAII Scope, invoking hook on every mutable access to value, running closure in destructor.
+///
+///Created via [tracked](struct.Scope.html#method.tracked).
+///
+///## Example
+///
+///```rust
+///use scope_guard::Scope;
+///use core::cell::Cell;
+///
+///let mutations = Cell::new(0);
+///let is_dirty = Cell::new(false);
+///{
+///    let mut guard = Scope::new(0, |_| is_dirty.set(mutations.get() > 0)).tracked(|| {
+///        mutations.set(mutations.get() + 1);
+///    });
+///    assert_eq!(*guard, 0);
+///    *guard += 1;
+///    *guard += 1;
+///    assert_eq!(mutations.get(), 2);
+///}
+///assert!(is_dirty.get());
+///```
+pub struct TrackedScope<T, F: FnOnce(T), H: FnMut()> {
+    scope: Scope<T, F>,
+    hook: H,
+}
+
+impl<T, F: FnOnce(T)> Scope<T, F> {
+    #[inline(always)]
+    ///Adds `hook` to be invoked every time value is accessed mutably.
+    pub fn tracked<H: FnMut()>(self, hook: H) -> TrackedScope<T, F, H> {
+        TrackedScope {
+            scope: self,
+            hook,
+        }
+    }
+}
+
+impl<T, F: FnOnce(T), H: FnMut()> TrackedScope<T, F, H> {
+    #[inline]
+    ///Returns underlying data, without executing destructor;
+    pub fn into_inner(self) -> T {
+        self.scope.into_inner()
+    }
+
+    #[inline]
+    ///Forgets self, preventing closure from running
+    pub fn forget(self) {
+        self.scope.forget()
+    }
+}
+
+impl<T, F: FnOnce(T), H: FnMut()> Deref for TrackedScope<T, F, H> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.scope
+    }
+}
+
+impl<T, F: FnOnce(T), H: FnMut()> DerefMut for TrackedScope<T, F, H> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        (self.hook)();
+        &mut self.scope
+    }
+}
@@ -0,0 +1,85 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::Scope;
+
+///RAII Scope, running rollback closure in destructor unless transaction is committed.
+///
+///Usually created via [commit_guard](macro.commit_guard.html).
+pub struct Transaction<T, F: FnOnce(T)>(Scope<T, F>);
+
+impl<T, F: FnOnce(T)> Transaction<T, F> {
+    #[inline(always)]
+    ///Creates new instance
+    pub fn new(val: T, rollback: F) -> Self {
+        Self(Scope::new(val, rollback))
+    }
+
+    #[inline]
+    ///Commits transaction, returning underlying data without executing rollback.
+    pub fn commit(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<T, F: FnOnce(T)> Deref for Transaction<T, F> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, F: FnOnce(T)> DerefMut for Transaction<T, F> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[macro_export]
+///Creates [Transaction](struct.Transaction.html) over resource, running rollback closure unless
+///committed.
+///
+///Multiple resources are specified before `=>` and packed into tuple.
+///
+///```
+///use scope_guard::commit_guard;
+///
+///fn transfer(from: &mut u32, to: &mut u32, amount: u32) -> Result<(), ()> {
+///    let (old_from, old_to) = (*from, *to);
+///    let mut accounts = commit_guard!(from, to => |(from, to): (&mut u32, &mut u32)| {
+///        *from = old_from;
+///        *to = old_to;
+///    });
+///
+///    *accounts.1 += amount;
+///    if *accounts.0 < amount {
+///        return Err(());
+///    }
+///    *accounts.0 -= amount;
+///    accounts.commit();
+///    Ok(())
+///}
+///
+///let mut from = 10;
+///let mut to = 0;
+///assert!(transfer(&mut from, &mut to, 20).is_err());
+///assert_eq!((from, to), (10, 0));
+///
+///assert!(transfer(&mut from, &mut to, 5).is_ok());
+///assert_eq!((from, to), (5, 5));
+///
+///let mut log = Vec::new();
+///let guard = commit_guard!(&mut log, |log: &mut Vec<&str>| log.push("rollback"));
+///guard.commit().push("commit");
+///assert_eq!(log, ["commit"]);
+///```
+macro_rules! commit_guard {
+    ($resource:expr, $rollback:expr) => {
+        $crate::Transaction::new($resource, $rollback)
+    };
+    ($($resource:expr),+ => $rollback:expr) => {
+        $crate::Transaction::new(($($resource),+), $rollback)
+    };
+}
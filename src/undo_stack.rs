@@ -0,0 +1,120 @@
+use core::mem;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+///RAII stack of undo steps, running all of them in reverse order on drop unless committed.
+///
+///Unlike [ArrayScope](struct.ArrayScope.html), steps are arbitrary closures, independent of any
+///guarded value.
+///
+///With `std` feature, panic of step does not prevent remaining steps from running: first panic is
+///resumed after all steps finished, unless thread is already panicking.
+///
+///## Example
+///
+///```rust
+///use scope_guard::UndoStack;
+///use core::cell::RefCell;
+///
+///let log = &RefCell::new(Vec::new());
+///{
+///    let mut undo = UndoStack::new();
+///    for step in 0..3 {
+///        undo.push_undo(move || log.borrow_mut().push(step));
+///    }
+///    assert_eq!(undo.len(), 3);
+///}
+///assert_eq!(*log.borrow(), [2, 1, 0]);
+///
+///log.borrow_mut().clear();
+///let mut undo = UndoStack::new();
+///undo.push_undo(|| log.borrow_mut().push(0));
+///undo.commit();
+///assert!(log.borrow().is_empty());
+///```
+pub struct UndoStack<'a> {
+    steps: Vec<Box<dyn FnOnce() + 'a>>,
+}
+
+impl<'a> UndoStack<'a> {
+    #[inline(always)]
+    ///Creates new empty instance
+    pub const fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+        }
+    }
+
+    #[inline]
+    ///Registers undo `step`, to be executed before all previously registered ones.
+    pub fn push_undo<S: FnOnce() + 'a>(&mut self, step: S) {
+        self.steps.push(Box::new(step));
+    }
+
+    #[inline(always)]
+    ///Returns number of registered steps.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    #[inline(always)]
+    ///Returns whether no steps are registered.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    #[inline]
+    ///Commits operation, dropping all steps without running them.
+    pub fn commit(mut self) {
+        drop(mem::take(&mut self.steps));
+    }
+}
+
+impl Default for UndoStack<'_> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for UndoStack<'_> {
+    #[cfg(not(feature = "std"))]
+    fn drop(&mut self) {
+        while let Some(step) = self.steps.pop() {
+            step();
+        }
+    }
+
+    #[cfg(feature = "std")]
+    ///Runs all steps in reverse order, even if some of them panic.
+    ///
+    ///```
+    ///use scope_guard::UndoStack;
+    ///use core::cell::Cell;
+    ///
+    ///let runs = Cell::new(0);
+    ///let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    ///    let mut undo = UndoStack::new();
+    ///    undo.push_undo(|| runs.set(runs.get() + 1));
+    ///    undo.push_undo(|| panic!("undo failed"));
+    ///    undo.push_undo(|| runs.set(runs.get() + 1));
+    ///}));
+    ///assert_eq!(*result.unwrap_err().downcast_ref::<&str>().unwrap(), "undo failed");
+    ///assert_eq!(runs.get(), 2);
+    ///```
+    fn drop(&mut self) {
+        let mut panic = None;
+        while let Some(step) = self.steps.pop() {
+            if let Err(error) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(step)) {
+                panic.get_or_insert(error);
+            }
+        }
+
+        if let Some(error) = panic {
+            if !std::thread::panicking() {
+                crate::strategy::resume_unwind(error);
+            }
+        }
+    }
+}